@@ -0,0 +1,65 @@
+use std::rc::Rc;
+
+use crate::ptree::{Node, Prefix, Ptree};
+
+impl<P, D> Ptree<P, D>
+where
+    P: Prefix + Copy,
+    D: Clone,
+{
+    /// Returns a copy of the table where sibling entries are replaced by
+    /// their covering prefix whenever `merge` combines their data.  This is
+    /// repeated bottom up, and no aggregate shorter than `min_len` is made.
+    pub fn aggregate<F>(&self, min_len: u8, merge: F) -> Ptree<P, D>
+    where
+        F: Fn(&D, &D) -> Option<D>,
+    {
+        let mut tree = Ptree::new();
+        for node in self.iter() {
+            if let Some(data) = node.data.borrow().as_ref() {
+                tree.add(&node.prefix, data.clone());
+            }
+        }
+        if let Some(top) = tree.top.clone() {
+            tree.aggregate_node(top, min_len, &merge);
+        }
+        tree
+    }
+
+    fn aggregate_node<F>(&mut self, node: Rc<Node<P, D>>, min_len: u8, merge: &F)
+    where
+        F: Fn(&D, &D) -> Option<D>,
+    {
+        for bit in 0..2 {
+            if let Some(child) = node.child_with(bit) {
+                self.aggregate_node(child, min_len, merge);
+            }
+        }
+
+        let (Some(left), Some(right)) = (node.child_with(0), node.child_with(1)) else {
+            return;
+        };
+        let common = P::from_common(&left.prefix, &right.prefix);
+        if common.prefix_len() < min_len
+            || left.prefix.prefix_len() != common.prefix_len() + 1
+            || right.prefix.prefix_len() != common.prefix_len() + 1
+        {
+            return;
+        }
+
+        let merged = match (&*left.data.borrow(), &*right.data.borrow()) {
+            (Some(l), Some(r)) => merge(l, r),
+            _ => None,
+        };
+        let merged = match (merged, &*node.data.borrow()) {
+            (Some(merged), Some(data)) => merge(data, &merged),
+            (merged, _) => merged,
+        };
+
+        if let Some(merged) = merged {
+            self.add(&node.prefix, merged);
+            self.delete(&left.prefix);
+            self.delete(&right.prefix);
+        }
+    }
+}
@@ -0,0 +1,355 @@
+use std::net::{IpAddr, Ipv4Addr};
+use std::str::FromStr;
+
+use crate::error::{at_line, Error};
+use crate::ptree::{parse_prefix, Prefix, Ptree};
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Nexthop {
+    pub addr: Option<IpAddr>,
+    pub interface: Option<String>,
+}
+
+/// A route as shown by a router's CLI.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Route {
+    pub protocol: String,
+    pub distance: Option<u32>,
+    pub metric: Option<u32>,
+    pub nexthops: Vec<Nexthop>,
+}
+
+fn parse_addr(str: &str) -> Result<IpAddr, Error> {
+    str.parse().map_err(|_| Error::Parse(str.to_string()))
+}
+
+fn parse_u32(str: &str) -> Result<u32, Error> {
+    str.parse().map_err(|_| Error::InvalidData(str.to_string()))
+}
+
+// Parses the `via`/`dev` attributes of a nexthop, leaving the others.
+fn ip_route_nexthop<'a>(tokens: &[&'a str], nexthop: &mut Nexthop) -> Result<Vec<&'a str>, Error> {
+    let mut rest = Vec::new();
+    let mut tokens = tokens.iter();
+    while let Some(&token) = tokens.next() {
+        match token {
+            "via" => {
+                let mut addr = tokens.next().ok_or(Error::Truncated)?;
+                if *addr == "inet" || *addr == "inet6" {
+                    addr = tokens.next().ok_or(Error::Truncated)?;
+                }
+                nexthop.addr = Some(parse_addr(addr)?);
+            }
+            "dev" => {
+                nexthop.interface = Some(tokens.next().ok_or(Error::Truncated)?.to_string());
+            }
+            _ => rest.push(token),
+        }
+    }
+    Ok(rest)
+}
+
+fn ip_route_line<P>(line: &str) -> Result<(P, Route), Error>
+where
+    P: Prefix + FromStr,
+{
+    const TYPES: [&str; 8] = [
+        "unicast",
+        "local",
+        "broadcast",
+        "multicast",
+        "blackhole",
+        "unreachable",
+        "prohibit",
+        "throw",
+    ];
+
+    let mut tokens: Vec<&str> = line.split_whitespace().collect();
+    if TYPES.contains(&tokens[0]) {
+        tokens.remove(0);
+    }
+    let prefix = match tokens.first() {
+        Some(&"default") => P::default_route(),
+        Some(prefix) => parse_prefix(prefix)?,
+        None => return Err(Error::Truncated),
+    };
+
+    let mut route = Route {
+        protocol: "boot".to_string(),
+        ..Default::default()
+    };
+    let mut nexthop = Nexthop::default();
+    let rest = ip_route_nexthop(&tokens[1..], &mut nexthop)?;
+    if nexthop != Nexthop::default() {
+        route.nexthops.push(nexthop);
+    }
+
+    let mut rest = rest.into_iter();
+    while let Some(token) = rest.next() {
+        match token {
+            "proto" => route.protocol = rest.next().ok_or(Error::Truncated)?.to_string(),
+            "metric" => route.metric = Some(parse_u32(rest.next().ok_or(Error::Truncated)?)?),
+            _ => {}
+        }
+    }
+    Ok((prefix, route))
+}
+
+#[cfg(feature = "json")]
+fn frr_route(value: &serde_json::Value) -> Result<Route, Error> {
+    let number = |key| {
+        value
+            .get(key)
+            .and_then(serde_json::Value::as_u64)
+            .and_then(|n| u32::try_from(n).ok())
+    };
+    let mut route = Route {
+        protocol: value
+            .get("protocol")
+            .and_then(serde_json::Value::as_str)
+            .unwrap_or_default()
+            .to_string(),
+        distance: number("distance"),
+        metric: number("metric"),
+        nexthops: Vec::new(),
+    };
+    for nexthop in value
+        .get("nexthops")
+        .and_then(serde_json::Value::as_array)
+        .into_iter()
+        .flatten()
+    {
+        let addr = match nexthop.get("ip").and_then(serde_json::Value::as_str) {
+            Some(addr) => Some(parse_addr(addr)?),
+            None => None,
+        };
+        let interface = nexthop
+            .get("interfaceName")
+            .and_then(serde_json::Value::as_str)
+            .map(str::to_string);
+        route.nexthops.push(Nexthop { addr, interface });
+    }
+    Ok(route)
+}
+
+fn cisco_protocol(code: &str) -> &'static str {
+    match code.trim_end_matches('*') {
+        "C" => "connected",
+        "L" => "local",
+        "S" => "static",
+        "R" => "rip",
+        "M" => "mobile",
+        "B" => "bgp",
+        "D" => "eigrp",
+        "O" => "ospf",
+        "i" => "isis",
+        "o" => "odr",
+        "P" => "periodic",
+        "H" => "nhrp",
+        "l" => "lisp",
+        _ => "unknown",
+    }
+}
+
+// Parses `[distance/metric] via addr, [age,] [interface]`,
+// `is directly connected, interface` or `is a summary, age, interface`.
+fn cisco_nexthop(text: &str, route: &mut Route) -> Result<(), Error> {
+    let mut text = text.trim();
+    if let Some(rest) = text.strip_prefix('[') {
+        let (values, rest) = rest.split_once(']').ok_or(Error::Truncated)?;
+        let (distance, metric) = values.split_once('/').ok_or(Error::Truncated)?;
+        route.distance = Some(parse_u32(distance)?);
+        route.metric = Some(parse_u32(metric)?);
+        text = rest.trim();
+    }
+
+    let mut nexthop = Nexthop::default();
+    if let Some(rest) = text
+        .strip_prefix("is directly connected,")
+        .or_else(|| text.strip_prefix("is a summary,"))
+    {
+        nexthop.interface = rest
+            .split(',')
+            .map(str::trim)
+            .next_back()
+            .filter(|s| !s.is_empty())
+            .map(str::to_string);
+    } else if let Some(rest) = text.strip_prefix("via ") {
+        let mut fields = rest.split(',').map(str::trim);
+        nexthop.addr = Some(parse_addr(fields.next().unwrap_or_default())?);
+        // The interface is the last field unless it is the age of the route.
+        nexthop.interface = fields
+            .next_back()
+            .filter(|s| !s.is_empty() && !s.starts_with(|c: char| c.is_ascii_digit()))
+            .map(str::to_string);
+    } else {
+        return Err(Error::InvalidData(text.to_string()));
+    }
+    route.nexthops.push(nexthop);
+    Ok(())
+}
+
+// The classful network of an IPv4 address, which the length in an
+// `is subnetted` header applies to.
+fn classful<P>(addr: &str) -> Result<P, Error>
+where
+    P: Prefix + FromStr,
+{
+    let first = addr
+        .parse::<Ipv4Addr>()
+        .map_err(|_| Error::Parse(addr.to_string()))?
+        .octets()[0];
+    let len = match first {
+        0..=127 => 8,
+        128..=191 => 16,
+        _ => 24,
+    };
+    Ok(parse_prefix::<P>(&format!("{}/{}", addr, len))?.to_masked())
+}
+
+impl<P> Ptree<P, Route>
+where
+    P: Prefix + Copy + FromStr,
+{
+    /// Adds the routes of `ip route show` output.  Routes spanning several
+    /// lines get one nexthop per `nexthop` line.  Returns the number of
+    /// routes added.
+    pub fn read_ip_route(&mut self, text: &str) -> Result<usize, Error> {
+        let mut routes: Vec<(P, Route)> = Vec::new();
+        for (index, line) in text.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            if let Some(rest) = line.trim().strip_prefix("nexthop ") {
+                let (_, route) = routes.last_mut().ok_or_else(|| {
+                    at_line(index + 1)(Error::InvalidData(line.trim().to_string()))
+                })?;
+                let tokens: Vec<&str> = rest.split_whitespace().collect();
+                let mut nexthop = Nexthop::default();
+                ip_route_nexthop(&tokens, &mut nexthop).map_err(at_line(index + 1))?;
+                route.nexthops.push(nexthop);
+            } else {
+                routes.push(ip_route_line(line).map_err(at_line(index + 1))?);
+            }
+        }
+        Ok(self.add_routes(routes))
+    }
+
+    /// Adds the routes of FRR `show ip route json` or `show ipv6 route json`
+    /// output.  Of several routes for a prefix the selected one is used.
+    #[cfg(feature = "json")]
+    pub fn read_frr_json(&mut self, text: &str) -> Result<usize, Error> {
+        let value: serde_json::Value =
+            serde_json::from_str(text).map_err(|err| Error::Json(err.to_string()))?;
+        let prefixes = value
+            .as_object()
+            .ok_or_else(|| Error::Json("expected an object".to_string()))?;
+        let mut routes: Vec<(P, Route)> = Vec::new();
+        for (prefix, candidates) in prefixes.iter() {
+            let prefix: P = parse_prefix(prefix)?;
+            let candidates = candidates.as_array().map(Vec::as_slice).unwrap_or_default();
+            let selected = candidates
+                .iter()
+                .find(|route| {
+                    route.get("selected").and_then(serde_json::Value::as_bool) == Some(true)
+                })
+                .or(candidates.first());
+            if let Some(route) = selected {
+                routes.push((prefix, frr_route(route)?));
+            }
+        }
+        Ok(self.add_routes(routes))
+    }
+
+    /// Adds the routes of Cisco style `show ip route` output.  Legends,
+    /// the gateway of last resort and subnet summary lines are skipped, and
+    /// prefixes without a length take it from the preceding `is subnetted`
+    /// line, which must cover their classful network.
+    pub fn read_cisco(&mut self, text: &str) -> Result<usize, Error> {
+        let mut routes: Vec<(P, Route)> = Vec::new();
+        let mut legend = false;
+        // The classful network of the last `is subnetted` header and the
+        // length of its subnets.
+        let mut subnet: Option<(P, String)> = None;
+
+        for (index, line) in text.lines().enumerate() {
+            if line.trim().is_empty() {
+                legend = false;
+                continue;
+            }
+            if line.starts_with("Codes:") {
+                legend = true;
+            }
+            if legend || line.starts_with("Gateway of last resort") {
+                continue;
+            }
+
+            let mut tokens = line.split_whitespace().peekable();
+            if line.starts_with(char::is_whitespace) {
+                if line.trim_start().starts_with('[') {
+                    let (_, route) = routes.last_mut().ok_or_else(|| {
+                        at_line(index + 1)(Error::InvalidData(line.trim().to_string()))
+                    })?;
+                    cisco_nexthop(line, route).map_err(at_line(index + 1))?;
+                } else if line.contains(" is variably subnetted") {
+                    subnet = None;
+                } else if line.contains(" is subnetted") {
+                    subnet = match tokens.next().unwrap_or_default().split_once('/') {
+                        Some((addr, len)) => {
+                            Some((classful(addr).map_err(at_line(index + 1))?, len.to_string()))
+                        }
+                        None => None,
+                    };
+                }
+                continue;
+            }
+
+            // A protocol code, optionally followed by sub codes like IA or E2.
+            let code = tokens.next().unwrap_or_default();
+            let mut route = Route {
+                protocol: cisco_protocol(code).to_string(),
+                ..Default::default()
+            };
+            while tokens
+                .peek()
+                .is_some_and(|token| !token.starts_with(|c: char| c.is_ascii_digit() || c == ':'))
+            {
+                tokens.next();
+            }
+            let prefix = tokens.next().unwrap_or_default();
+            let prefix: P = match &subnet {
+                _ if prefix.contains('/') => {
+                    subnet = None;
+                    parse_prefix(prefix)
+                }
+                Some((network, len)) => {
+                    parse_prefix(&format!("{}/{}", prefix, len)).and_then(|subnet: P| {
+                        if network.contains(&subnet) {
+                            Ok(subnet)
+                        } else {
+                            Err(Error::Parse(prefix.to_string()))
+                        }
+                    })
+                }
+                None => parse_prefix(prefix),
+            }
+            .map_err(at_line(index + 1))?;
+
+            // Long prefixes push the nexthop onto a continuation line.
+            let rest = tokens.collect::<Vec<_>>().join(" ");
+            if !rest.is_empty() {
+                cisco_nexthop(&rest, &mut route).map_err(at_line(index + 1))?;
+            }
+            routes.push((prefix, route));
+        }
+        Ok(self.add_routes(routes))
+    }
+
+    fn add_routes(&mut self, routes: Vec<(P, Route)>) -> usize {
+        let count = routes.len();
+        for (prefix, route) in routes.into_iter() {
+            self.add(&prefix, route);
+        }
+        count
+    }
+}
@@ -0,0 +1,92 @@
+use std::collections::BTreeSet;
+
+use crate::diff::{descend, Link};
+use crate::ptree::{Prefix, Ptree};
+
+// A region of the leaf pushed binary trie.  `set` is the ORTC candidate set,
+// which is only meaningful when every address of the region is routed.
+struct Region<P, D> {
+    prefix: P,
+    covered: bool,
+    set: BTreeSet<D>,
+    children: Option<Box<[Region<P, D>; 2]>>,
+}
+
+fn build<P, D>(prefix: P, node: Link<P, D>, mut best: Link<P, D>) -> Region<P, D>
+where
+    P: Prefix + Copy,
+    D: Clone + Ord,
+{
+    let [left, right] = descend(&prefix, &node, &mut best);
+
+    if left.is_none() && right.is_none() {
+        let data = best.and_then(|node| node.data.borrow().clone());
+        return Region {
+            prefix,
+            covered: data.is_some(),
+            set: data.into_iter().collect(),
+            children: None,
+        };
+    }
+
+    let left = build(prefix.child(0), left, best.clone());
+    let right = build(prefix.child(1), right, best);
+
+    let covered = left.covered && right.covered;
+    let set = if !covered {
+        BTreeSet::new()
+    } else if left.set.is_disjoint(&right.set) {
+        left.set.union(&right.set).cloned().collect()
+    } else {
+        left.set.intersection(&right.set).cloned().collect()
+    };
+
+    Region {
+        prefix,
+        covered,
+        set,
+        children: Some(Box::new([left, right])),
+    }
+}
+
+fn assign<'a, P, D>(region: &'a Region<P, D>, inherited: Option<&'a D>, tree: &mut Ptree<P, D>)
+where
+    P: Prefix + Copy,
+    D: Clone + Ord,
+{
+    let selected = if !region.covered {
+        None
+    } else if inherited.is_some_and(|data| region.set.contains(data)) {
+        inherited
+    } else {
+        let data = region.set.first();
+        if let Some(data) = data {
+            tree.add(&region.prefix, data.clone());
+        }
+        data
+    };
+
+    if let Some(children) = &region.children {
+        for child in children.iter() {
+            assign(child, selected, tree);
+        }
+    }
+}
+
+impl<P, D> Ptree<P, D>
+where
+    P: Prefix + Copy,
+    D: Clone + Ord,
+{
+    /// Returns the smallest table whose `lookup()` yields the same data as
+    /// this one for every address, computed with ORTC.  Addresses without a
+    /// route stay unrouted, so no entry is ever placed over them.
+    pub fn compress(&self) -> Ptree<P, D> {
+        let mut tree = Ptree::new();
+        if let Some(top) = self.top.clone() {
+            let region = build(top.prefix, Some(top), None);
+            assign(&region, None, &mut tree);
+        }
+        tree
+    }
+}
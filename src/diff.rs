@@ -0,0 +1,127 @@
+use std::rc::Rc;
+
+use crate::ptree::{Node, Prefix, Ptree};
+
+pub(crate) type Link<P, D> = Option<Rc<Node<P, D>>>;
+
+pub(crate) enum Cover<P> {
+    Empty,
+    Full,
+    Parts(Vec<P>),
+}
+
+impl<P> Cover<P> {
+    pub(crate) fn into_vec(self, prefix: P) -> Vec<P> {
+        match self {
+            Cover::Empty => Vec::new(),
+            Cover::Full => vec![prefix],
+            Cover::Parts(parts) => parts,
+        }
+    }
+}
+
+pub(crate) fn descend<P, D>(prefix: &P, node: &Link<P, D>, best: &mut Link<P, D>) -> [Link<P, D>; 2]
+where
+    P: Prefix + Copy,
+{
+    match node {
+        None => [None, None],
+        Some(node) if node.prefix.prefix_len() == prefix.prefix_len() => {
+            if node.has_data() {
+                *best = Some(node.clone());
+            }
+            [node.child_with(0), node.child_with(1)]
+        }
+        Some(node) => {
+            if node.prefix.bit_at(prefix.prefix_len()) == 0 {
+                [Some(node.clone()), None]
+            } else {
+                [None, Some(node.clone())]
+            }
+        }
+    }
+}
+
+// Walks the address space under `prefix` in both tries at once, splitting
+// only where either trie has a node.  `f` is given the longest matching data
+// node of each trie for a uniform region and decides whether it is selected.
+// `a` and `b` must be the topmost nodes contained in `prefix`.
+pub(crate) fn cover<P, A, B, F>(
+    prefix: P,
+    a: Link<P, A>,
+    b: Link<P, B>,
+    mut best_a: Link<P, A>,
+    mut best_b: Link<P, B>,
+    f: &F,
+) -> Cover<P>
+where
+    P: Prefix + Copy,
+    F: Fn(&Link<P, A>, &Link<P, B>) -> bool,
+{
+    let [a0, a1] = descend(&prefix, &a, &mut best_a);
+    let [b0, b1] = descend(&prefix, &b, &mut best_b);
+
+    if a0.is_none() && a1.is_none() && b0.is_none() && b1.is_none() {
+        return if f(&best_a, &best_b) {
+            Cover::Full
+        } else {
+            Cover::Empty
+        };
+    }
+
+    let left = cover(prefix.child(0), a0, b0, best_a.clone(), best_b.clone(), f);
+    let right = cover(prefix.child(1), a1, b1, best_a, best_b, f);
+
+    match (left, right) {
+        (Cover::Full, Cover::Full) => Cover::Full,
+        (Cover::Empty, Cover::Empty) => Cover::Empty,
+        (left, right) => {
+            let mut parts = left.into_vec(prefix.child(0));
+            parts.extend(right.into_vec(prefix.child(1)));
+            Cover::Parts(parts)
+        }
+    }
+}
+
+// Same as `cover()` over the whole address space.  Regions outside of both
+// tries are never selected, so `f(None, None)` must be false.
+pub(crate) fn cover_all<P, A, B, F>(a: &Ptree<P, A>, b: &Ptree<P, B>, f: &F) -> Vec<P>
+where
+    P: Prefix + Copy,
+    F: Fn(&Link<P, A>, &Link<P, B>) -> bool,
+{
+    let prefix = match (&a.top, &b.top) {
+        (Some(a), Some(b)) => P::from_common(&a.prefix, &b.prefix),
+        (Some(a), None) => a.prefix,
+        (None, Some(b)) => b.prefix,
+        (None, None) => return Vec::new(),
+    };
+    cover(prefix, a.top.clone(), b.top.clone(), None, None, f).into_vec(prefix)
+}
+
+fn same_data<P, D>(a: &Link<P, D>, b: &Link<P, D>) -> bool
+where
+    D: PartialEq,
+{
+    match (a, b) {
+        (None, None) => true,
+        (Some(a), Some(b)) => *a.data.borrow() == *b.data.borrow(),
+        _ => false,
+    }
+}
+
+impl<P, D> Ptree<P, D>
+where
+    P: Prefix + Copy,
+    D: PartialEq,
+{
+    /// Returns the minimal set of prefixes, in tree order, for which
+    /// `lookup()` returns different data in `self` and `other`.
+    pub fn forwarding_diff(&self, other: &Ptree<P, D>) -> Vec<P> {
+        cover_all(self, other, &|a, b| !same_data(a, b))
+    }
+
+    pub fn forwarding_equivalent(&self, other: &Ptree<P, D>) -> bool {
+        self.forwarding_diff(other).is_empty()
+    }
+}
@@ -0,0 +1,51 @@
+use std::fmt;
+use std::io::Write;
+
+use crate::error::Error;
+use crate::ptree::{Prefix, Ptree};
+
+impl<P, D> Ptree<P, D>
+where
+    P: Prefix + Copy + fmt::Display,
+{
+    /// Writes the trie as a Graphviz digraph, or only the part of it within
+    /// `within`.  Data nodes are boxes, glue nodes dashed ellipses, and each
+    /// edge is labelled with the bit selecting the child.
+    pub fn write_dot<W>(&self, mut writer: W, within: Option<&P>) -> Result<(), Error>
+    where
+        W: Write,
+    {
+        let top = match within {
+            Some(prefix) => self.subtree(&prefix.to_masked()),
+            None => self.top.clone(),
+        };
+
+        writeln!(writer, "digraph ptree {{")?;
+        writeln!(writer, "\tnode [fontname=\"monospace\"];")?;
+        let mut stack: Vec<(Option<usize>, u8, _)> =
+            top.into_iter().map(|n| (None, 0, n)).collect();
+        let mut id = 0;
+        while let Some((parent, bit, node)) = stack.pop() {
+            if node.has_data() {
+                writeln!(writer, "\tn{} [label=\"{}\", shape=box];", id, node.prefix)?;
+            } else {
+                writeln!(
+                    writer,
+                    "\tn{} [label=\"{}\", shape=ellipse, style=dashed];",
+                    id, node.prefix
+                )?;
+            }
+            if let Some(parent) = parent {
+                writeln!(writer, "\tn{} -> n{} [label=\"{}\"];", parent, id, bit)?;
+            }
+            for bit in [1, 0] {
+                if let Some(child) = node.child_with(bit) {
+                    stack.push((Some(id), bit, child));
+                }
+            }
+            id += 1;
+        }
+        writeln!(writer, "}}")?;
+        Ok(())
+    }
+}
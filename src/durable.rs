@@ -0,0 +1,218 @@
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use crate::error::Error;
+use crate::ptree::{Prefix, Ptree};
+use crate::snapshot::Snapshot;
+use crate::wire::{crc32, Decoder};
+
+const SNAPSHOT: &str = "snapshot";
+const SNAPSHOT_TMP: &str = "snapshot.tmp";
+const LOG: &str = "log";
+const HEADER_LEN: usize = 12;
+
+const OP_ADD: u8 = 1;
+const OP_DELETE: u8 = 2;
+
+type Encode<D> = Box<dyn Fn(&D) -> Vec<u8>>;
+
+/// A table kept in a directory as a snapshot plus a write-ahead log of the
+/// changes made since.  Every change is appended to the log before it is
+/// applied, and a new snapshot replaces the log every `snapshot_interval`
+/// changes.
+pub struct Durable<P, D> {
+    tree: Ptree<P, D>,
+    dir: PathBuf,
+    log: File,
+    encode: Encode<D>,
+    changes: usize,
+    snapshot_interval: usize,
+}
+
+impl<P, D> Durable<P, D>
+where
+    P: Prefix + Copy,
+{
+    /// Opens the table in `dir`, creating it if needed, and rebuilds it from
+    /// the snapshot and the log.  A torn record at the end of the log, as
+    /// left by a crash in the middle of a write, is discarded.
+    pub fn open<Q, E, F>(dir: Q, encode: E, decode: F) -> Result<Self, Error>
+    where
+        Q: AsRef<Path>,
+        E: Fn(&D) -> Vec<u8> + 'static,
+        F: Fn(&[u8]) -> Option<D>,
+    {
+        let dir = dir.as_ref().to_path_buf();
+        fs::create_dir_all(&dir)?;
+        let decode = |bytes: &[u8]| {
+            decode(bytes).ok_or_else(|| Error::InvalidData(format!("{:02x?}", bytes)))
+        };
+
+        let mut tree = Ptree::new();
+        match fs::read(dir.join(SNAPSHOT)) {
+            Ok(bytes) => {
+                for (prefix, data) in Snapshot::<P>::open(&bytes)?.iter() {
+                    tree.add(&prefix, decode(data)?);
+                }
+            }
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => {}
+            Err(err) => return Err(err.into()),
+        }
+
+        let log_path = dir.join(LOG);
+        let bytes = match fs::read(&log_path) {
+            Ok(bytes) => bytes,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Vec::new(),
+            Err(err) => return Err(err.into()),
+        };
+        let mut valid = 0;
+        let mut changes = 0;
+        while valid < bytes.len() {
+            let Some(len) = replay(&mut tree, &bytes[valid..], &decode)? else {
+                break;
+            };
+            valid += len;
+            changes += 1;
+        }
+
+        let log = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&log_path)?;
+        if valid < bytes.len() {
+            log.set_len(valid as u64)?;
+        }
+
+        Ok(Durable {
+            tree,
+            dir,
+            log,
+            encode: Box::new(encode),
+            changes,
+            snapshot_interval: 100_000,
+        })
+    }
+
+    pub fn tree(&self) -> &Ptree<P, D> {
+        &self.tree
+    }
+
+    /// Sets the number of changes after which a snapshot is written, or 0
+    /// to only write them by calling `snapshot()`.
+    pub fn set_snapshot_interval(&mut self, changes: usize) {
+        self.snapshot_interval = changes;
+    }
+
+    pub fn add(&mut self, prefix: &P, data: D) -> Result<(), Error> {
+        let mut payload = vec![OP_ADD];
+        push_prefix(&mut payload, &prefix.to_masked());
+        payload.extend_from_slice(&(self.encode)(&data));
+        self.append(&payload)?;
+        self.tree.add(prefix, data);
+        self.changed()
+    }
+
+    pub fn delete(&mut self, prefix: &P) -> Result<(), Error> {
+        let mut payload = vec![OP_DELETE];
+        push_prefix(&mut payload, &prefix.to_masked());
+        self.append(&payload)?;
+        self.tree.delete(prefix);
+        self.changed()
+    }
+
+    /// Writes a snapshot of the table and empties the log.
+    pub fn snapshot(&mut self) -> Result<(), Error> {
+        let tmp = self.dir.join(SNAPSHOT_TMP);
+        let mut writer = BufWriter::new(File::create(&tmp)?);
+        self.tree.write_snapshot(&mut writer, &self.encode)?;
+        writer
+            .into_inner()
+            .map_err(|err| err.into_error())?
+            .sync_all()?;
+        fs::rename(&tmp, self.dir.join(SNAPSHOT))?;
+        // The rename itself must reach the disk before the log is emptied,
+        // or a crash could leave the old snapshot next to an empty log.
+        File::open(&self.dir)?.sync_all()?;
+
+        // Replaying a log over a snapshot already containing its changes
+        // gives the same table, so a crash before this point is harmless.
+        self.log.set_len(0)?;
+        self.log.sync_all()?;
+        self.changes = 0;
+        Ok(())
+    }
+
+    /// Flushes the log to disk.  Without this a change survives a crash of
+    /// the process but not necessarily one of the system.
+    pub fn sync(&self) -> Result<(), Error> {
+        self.log.sync_data()?;
+        Ok(())
+    }
+
+    fn append(&mut self, payload: &[u8]) -> Result<(), Error> {
+        let mut record = Vec::with_capacity(payload.len() + HEADER_LEN);
+        record.extend_from_slice(&(payload.len() as u32).to_be_bytes());
+        record.extend_from_slice(&crc32(0, payload).to_be_bytes());
+        record.extend_from_slice(&crc32(0, &record).to_be_bytes());
+        record.extend_from_slice(payload);
+        self.log.write_all(&record)?;
+        Ok(())
+    }
+
+    fn changed(&mut self) -> Result<(), Error> {
+        self.changes += 1;
+        if self.snapshot_interval > 0 && self.changes >= self.snapshot_interval {
+            self.snapshot()?;
+        }
+        Ok(())
+    }
+}
+
+fn push_prefix<P: Prefix>(payload: &mut Vec<u8>, prefix: &P) {
+    payload.push(prefix.prefix_len());
+    payload.extend_from_slice(&prefix.octets());
+}
+
+// Applies the first log record in `bytes`, returning its length, or `None`
+// when it is the torn last record running past the end of the log.  Any
+// other damage is an error, since the records after it would be lost.
+//
+// A record is its payload length u32, the CRC-32 of the payload u32 and the
+// CRC-32 of these two u32, followed by the payload.  With the length covered
+// by its own checksum, a damaged length cannot pass for a torn record.
+fn replay<P, D, F>(tree: &mut Ptree<P, D>, bytes: &[u8], decode: &F) -> Result<Option<usize>, Error>
+where
+    P: Prefix + Copy,
+    F: Fn(&[u8]) -> Result<D, Error>,
+{
+    if bytes.len() < HEADER_LEN {
+        return Ok(None);
+    }
+    let mut decoder = Decoder::new(bytes);
+    let len = decoder.u32()?;
+    let checksum = decoder.u32()?;
+    if crc32(0, &bytes[..8]) != decoder.u32()? {
+        return Err(Error::Checksum);
+    }
+    let Ok(payload) = decoder.bytes(len as usize) else {
+        return Ok(None);
+    };
+    if crc32(0, payload) != checksum {
+        return Err(Error::Checksum);
+    }
+
+    let mut decoder = Decoder::new(payload);
+    let op = decoder.u8()?;
+    let prefix_len = decoder.u8()?;
+    let octets = decoder.bytes(P::MAX_LEN as usize / 8)?;
+    let prefix =
+        P::from_octets(octets, prefix_len).ok_or(Error::InvalidPrefixLength(prefix_len))?;
+    let data = &payload[2 + octets.len()..];
+    match op {
+        OP_ADD => tree.add(&prefix, decode(data)?),
+        OP_DELETE => tree.delete(&prefix),
+        _ => return Err(Error::InvalidData(format!("log operation {}", op))),
+    }
+    Ok(Some(HEADER_LEN + payload.len()))
+}
@@ -0,0 +1,129 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use crate::ptree::{Prefix, Ptree};
+
+/// A set of weighted next hops shared by the prefixes pointing to it.
+/// Flows are spread with a fixed table of buckets each owned by a member,
+/// so a change to the members only moves the flows of the buckets that
+/// have to change owner.
+#[derive(Debug)]
+pub struct NexthopGroup<N> {
+    members: RefCell<Vec<(N, u32)>>,
+    buckets: RefCell<Vec<Option<N>>>,
+}
+
+impl<N> NexthopGroup<N>
+where
+    N: Clone + PartialEq,
+{
+    /// Creates an empty group spreading flows over `buckets` buckets, which
+    /// should be several times the number of members for an even spread.
+    pub fn new(buckets: usize) -> Self {
+        NexthopGroup {
+            members: RefCell::new(Vec::new()),
+            buckets: RefCell::new(vec![None; buckets.max(1)]),
+        }
+    }
+
+    /// Adds `member` or changes its weight.  A weight of 0 removes it.
+    pub fn add(&self, member: N, weight: u32) {
+        if weight == 0 {
+            self.remove(&member);
+            return;
+        }
+        {
+            let mut members = self.members.borrow_mut();
+            match members.iter_mut().find(|(m, _)| *m == member) {
+                Some(entry) => entry.1 = weight,
+                None => members.push((member, weight)),
+            }
+        }
+        self.rebalance();
+    }
+
+    pub fn remove(&self, member: &N) -> bool {
+        let removed = {
+            let mut members = self.members.borrow_mut();
+            let len = members.len();
+            members.retain(|(m, _)| m != member);
+            members.len() != len
+        };
+        if removed {
+            self.rebalance();
+        }
+        removed
+    }
+
+    pub fn members(&self) -> Vec<(N, u32)> {
+        self.members.borrow().clone()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.members.borrow().is_empty()
+    }
+
+    /// The member carrying the flow with hash `flow_hash`.
+    pub fn select(&self, flow_hash: u32) -> Option<N> {
+        let buckets = self.buckets.borrow();
+        buckets[flow_hash as usize % buckets.len()].clone()
+    }
+
+    // Gives each member a share of the buckets proportional to its weight.
+    // Buckets stay with their member while it has no more than its share,
+    // and only the others are handed to members short of theirs.
+    fn rebalance(&self) {
+        let members = self.members.borrow();
+        let mut buckets = self.buckets.borrow_mut();
+        let total: u64 = members.iter().map(|(_, weight)| *weight as u64).sum();
+        if total == 0 {
+            buckets.iter_mut().for_each(|bucket| *bucket = None);
+            return;
+        }
+
+        let len = buckets.len() as u64;
+        let mut shares: Vec<usize> = members
+            .iter()
+            .map(|(_, weight)| (len * *weight as u64 / total) as usize)
+            .collect();
+        let remainder = buckets.len() - shares.iter().sum::<usize>();
+        for share in shares.iter_mut().take(remainder) {
+            *share += 1;
+        }
+
+        let mut counts = vec![0; members.len()];
+        for bucket in buckets.iter_mut() {
+            let owner = bucket
+                .as_ref()
+                .and_then(|n| members.iter().position(|(m, _)| m == n));
+            match owner {
+                Some(index) if counts[index] < shares[index] => counts[index] += 1,
+                _ => *bucket = None,
+            }
+        }
+
+        let mut index = 0;
+        for bucket in buckets.iter_mut().filter(|bucket| bucket.is_none()) {
+            while counts[index] == shares[index] {
+                index += 1;
+            }
+            *bucket = Some(members[index].0.clone());
+            counts[index] += 1;
+        }
+    }
+}
+
+impl<P, N> Ptree<P, Rc<NexthopGroup<N>>>
+where
+    P: Prefix + Copy,
+    N: Clone + PartialEq,
+{
+    /// Longest match of `addr`, returning the member of the matching group
+    /// selected by `flow_hash`.  The same hash always gives the same member
+    /// while the group is unchanged.
+    pub fn lookup_flow(&self, addr: &P, flow_hash: u32) -> Option<N> {
+        let node = self.lookup(addr).node?;
+        let data = node.data.borrow();
+        data.as_ref()?.select(flow_hash)
+    }
+}
@@ -0,0 +1,57 @@
+use std::fmt;
+use std::io;
+
+#[derive(Debug)]
+pub enum Error {
+    Parse(String),
+    HostBitsSet(String),
+    AddressFamilyMismatch(String),
+    InvalidData(String),
+    InvalidPrefixLength(u8),
+    Json(String),
+    Truncated,
+    Checksum,
+    UnsupportedVersion(u16),
+    Line(usize, Box<Error>),
+    Io(io::Error),
+}
+
+pub(crate) fn at_line(line: usize) -> impl Fn(Error) -> Error {
+    move |err| Error::Line(line, Box::new(err))
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Parse(str) => write!(f, "invalid prefix {:?}", str),
+            Error::HostBitsSet(prefix) => write!(f, "host bits set in {}", prefix),
+            Error::AddressFamilyMismatch(str) => {
+                write!(f, "{} does not match the address family of the table", str)
+            }
+            Error::InvalidData(str) => write!(f, "invalid data {:?}", str),
+            Error::InvalidPrefixLength(len) => write!(f, "invalid prefix length {}", len),
+            Error::Json(str) => write!(f, "invalid JSON: {}", str),
+            Error::Truncated => write!(f, "unexpected end of data"),
+            Error::Checksum => write!(f, "checksum mismatch"),
+            Error::UnsupportedVersion(version) => write!(f, "unsupported version {}", version),
+            Error::Line(line, err) => write!(f, "line {}: {}", line, err),
+            Error::Io(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Line(_, err) => Some(err.as_ref()),
+            Error::Io(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(err: io::Error) -> Self {
+        Error::Io(err)
+    }
+}
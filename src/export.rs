@@ -0,0 +1,137 @@
+use std::fmt;
+use std::io::Write;
+use std::rc::Rc;
+
+use crate::diff::{cover, cover_all, Link};
+use crate::error::Error;
+use crate::ptree::{Node, Prefix, Ptree};
+
+impl<P, D> Ptree<P, D>
+where
+    P: Prefix + Copy + fmt::Display,
+{
+    // Every writer takes `within` to mean the entries contained in it, so a
+    // covering entry is left out rather than clipped.
+    fn entries(&self, within: Option<&P>) -> Vec<Rc<Node<P, D>>> {
+        match within {
+            Some(prefix) => self.iter_within(prefix).collect(),
+            None => self.iter().collect(),
+        }
+    }
+
+    // The minimal prefixes covering the same space as the entries within
+    // `within`.
+    fn covered(&self, within: Option<&P>) -> Vec<P> {
+        let none: Link<P, ()> = None;
+        match within {
+            Some(prefix) => {
+                let prefix = prefix.to_masked();
+                cover(prefix, self.subtree(&prefix), none, None, None, &|a, _| {
+                    a.is_some()
+                })
+                .into_vec(prefix)
+            }
+            None => cover_all(self, &Ptree::<P, ()>::new(), &|a, _| a.is_some()),
+        }
+    }
+
+    /// Writes an nftables set named `name` with the interval elements
+    /// covering the table, or the entries contained in `within`.
+    /// Overlapping entries are merged as interval sets do not accept them.
+    pub fn write_nft_set<W>(
+        &self,
+        mut writer: W,
+        name: &str,
+        within: Option<&P>,
+    ) -> Result<(), Error>
+    where
+        W: Write,
+    {
+        let addr_type = if P::MAX_LEN == 32 {
+            "ipv4_addr"
+        } else {
+            "ipv6_addr"
+        };
+        writeln!(writer, "set {} {{", name)?;
+        writeln!(writer, "\ttype {}", addr_type)?;
+        writeln!(writer, "\tflags interval")?;
+        let elements = self.covered(within);
+        if !elements.is_empty() {
+            let elements: Vec<String> = elements.iter().map(|p| p.to_string()).collect();
+            writeln!(writer, "\telements = {{ {} }}", elements.join(", "))?;
+        }
+        writeln!(writer, "}}")?;
+        Ok(())
+    }
+
+    /// Writes `ipset restore` input creating the `hash:net` set `name` with
+    /// an element for each entry, or each one contained in `within`.
+    pub fn write_ipset<W>(&self, mut writer: W, name: &str, within: Option<&P>) -> Result<(), Error>
+    where
+        W: Write,
+    {
+        let family = if P::MAX_LEN == 32 { "inet" } else { "inet6" };
+        writeln!(writer, "create {} hash:net family {}", name, family)?;
+        for node in self.entries(within) {
+            writeln!(writer, "add {} {}", name, node.prefix)?;
+        }
+        Ok(())
+    }
+
+    /// Writes an `ip -batch` file adding a route for each entry, or each one
+    /// contained in `within`, `route` giving the rest of the command such as
+    /// `via 192.0.2.1 dev eth0`.
+    pub fn write_ip_batch<W, F>(
+        &self,
+        mut writer: W,
+        within: Option<&P>,
+        route: F,
+    ) -> Result<(), Error>
+    where
+        W: Write,
+        F: Fn(&D) -> String,
+    {
+        for node in self.entries(within) {
+            if let Some(data) = node.data.borrow().as_ref() {
+                writeln!(writer, "route add {} {}", node.prefix, route(data))?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Writes FRR/Cisco `ip prefix-list` statements for the entries, or the
+    /// ones contained in `within`, numbered from `seq` by `step`.  `permit`
+    /// decides the action of each entry.  Running out of sequence numbers is
+    /// an error.
+    pub fn write_prefix_list<W, F>(
+        &self,
+        mut writer: W,
+        name: &str,
+        seq: u32,
+        step: u32,
+        within: Option<&P>,
+        permit: F,
+    ) -> Result<(), Error>
+    where
+        W: Write,
+        F: Fn(&D) -> bool,
+    {
+        let family = if P::MAX_LEN == 32 { "ip" } else { "ipv6" };
+        let mut next = Some(seq);
+        for node in self.entries(within) {
+            if let Some(data) = node.data.borrow().as_ref() {
+                let seq = next.ok_or_else(|| {
+                    Error::InvalidData(format!("prefix-list sequence after {}", node.prefix))
+                })?;
+                let action = if permit(data) { "permit" } else { "deny" };
+                writeln!(
+                    writer,
+                    "{} prefix-list {} seq {} {} {}",
+                    family, name, seq, action, node.prefix
+                )?;
+                next = seq.checked_add(step);
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,100 @@
+use std::fmt;
+use std::io::{BufRead, Write};
+use std::str::FromStr;
+
+use crate::error::{at_line, Error};
+use crate::ptree::{parse_prefix, Prefix, Ptree};
+
+// Strips comments and surrounding whitespace, returning `None` for lines
+// without content.
+fn content(line: &str) -> Option<&str> {
+    let line = match line.find('#') {
+        Some(pos) => &line[..pos],
+        None => line,
+    };
+    let line = line.trim();
+    if line.is_empty() {
+        None
+    } else {
+        Some(line)
+    }
+}
+
+impl<P, D> Ptree<P, D>
+where
+    P: Prefix + Copy + FromStr + PartialEq + fmt::Display,
+{
+    /// Reads a file with one prefix per line and adds each of them with a
+    /// clone of `data`.  Text after `#` is a comment.  Returns the number of
+    /// prefixes read.
+    pub fn read_prefixes<R>(&mut self, reader: R, data: D) -> Result<usize, Error>
+    where
+        R: BufRead,
+        D: Clone,
+    {
+        let mut count = 0;
+        for (index, line) in reader.lines().enumerate() {
+            let line = line.map_err(Error::from).map_err(at_line(index + 1))?;
+            let Some(line) = content(&line) else {
+                continue;
+            };
+            self.add_str(line, data.clone())
+                .map_err(at_line(index + 1))?;
+            count += 1;
+        }
+        Ok(count)
+    }
+
+    /// Reads a file of routes, each line being a prefix followed by its data
+    /// which is the rest of the line parsed with `FromStr`.
+    pub fn read_routes<R>(&mut self, reader: R) -> Result<usize, Error>
+    where
+        R: BufRead,
+        D: FromStr,
+    {
+        let mut count = 0;
+        for (index, line) in reader.lines().enumerate() {
+            let line = line.map_err(Error::from).map_err(at_line(index + 1))?;
+            let Some(line) = content(&line) else {
+                continue;
+            };
+            let (prefix, data) = match line.split_once(char::is_whitespace) {
+                Some((prefix, data)) => (prefix, data.trim()),
+                None => (line, ""),
+            };
+            let prefix: P = parse_prefix(prefix).map_err(at_line(index + 1))?;
+            let data: D = data
+                .parse()
+                .map_err(|_| Error::InvalidData(data.to_string()))
+                .map_err(at_line(index + 1))?;
+            self.try_add(&prefix, data).map_err(at_line(index + 1))?;
+            count += 1;
+        }
+        Ok(count)
+    }
+
+    /// Writes the prefixes of all entries, one per line in tree order.
+    pub fn write_prefixes<W>(&self, mut writer: W) -> Result<(), Error>
+    where
+        W: Write,
+    {
+        for node in self.iter() {
+            writeln!(writer, "{}", node.prefix)?;
+        }
+        Ok(())
+    }
+
+    /// Writes all entries in the format read by `read_routes()`.
+    pub fn write_routes<W>(&self, mut writer: W) -> Result<(), Error>
+    where
+        W: Write,
+        D: fmt::Display,
+    {
+        for node in self.iter() {
+            if let Some(data) = node.data.borrow().as_ref() {
+                writeln!(writer, "{} {}", node.prefix, data)?;
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,19 @@
+use crate::diff::{cover, Link};
+use crate::ptree::{Prefix, Ptree};
+
+impl<P, D> Ptree<P, D>
+where
+    P: Prefix + Copy,
+{
+    /// Returns the minimal list of prefixes within `prefix` that are not
+    /// covered by any entry, in tree order.
+    pub fn gaps(&self, prefix: &P) -> Vec<P> {
+        let prefix = prefix.to_masked();
+        let best = self.lookup(&prefix).node;
+        let none: Link<P, ()> = None;
+        cover(prefix, self.subtree(&prefix), none, best, None, &|a, _| {
+            a.is_none()
+        })
+        .into_vec(prefix)
+    }
+}
@@ -0,0 +1,133 @@
+use std::fmt;
+use std::io::{self, Write};
+use std::rc::Rc;
+
+use serde::ser::{Serialize, SerializeMap, Serializer};
+
+use crate::error::Error;
+use crate::ptree::{Node, Prefix, Ptree};
+
+// `{"prefix": ..., "data": ...}` with `data` left out for glue nodes, and
+// followed by the `left` and `right` children in the nested form.
+struct Entry<'a, P, D, F> {
+    node: &'a Node<P, D>,
+    data: &'a F,
+    nested: bool,
+}
+
+impl<P, D, F, T> Serialize for Entry<'_, P, D, F>
+where
+    P: Prefix + Copy + fmt::Display,
+    F: Fn(&D) -> T,
+    T: Serialize,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut map = serializer.serialize_map(None)?;
+        map.serialize_entry("prefix", &self.node.prefix.to_string())?;
+        if let Some(value) = self.node.data.borrow().as_ref() {
+            map.serialize_entry("data", &(self.data)(value))?;
+        }
+        if self.nested {
+            for (bit, key) in ["left", "right"].iter().enumerate() {
+                if let Some(child) = self.node.child_with(bit as u8) {
+                    map.serialize_entry(key, &entry(&child, self.data, true))?;
+                }
+            }
+        }
+        map.end()
+    }
+}
+
+fn entry<'a, P, D, F>(node: &'a Node<P, D>, data: &'a F, nested: bool) -> Entry<'a, P, D, F> {
+    Entry { node, data, nested }
+}
+
+fn entries<'a, P, D, F>(nodes: &'a [Rc<Node<P, D>>], data: &'a F) -> Vec<Entry<'a, P, D, F>> {
+    nodes.iter().map(|node| entry(node, data, false)).collect()
+}
+
+fn write_value<W, T>(writer: W, value: &T) -> Result<(), Error>
+where
+    W: Write,
+    T: Serialize,
+{
+    serde_json::to_writer(writer, value).map_err(|err| io::Error::from(err).into())
+}
+
+impl<P, D> Ptree<P, D>
+where
+    P: Prefix + Copy + fmt::Display,
+{
+    /// Writes the entries as a JSON array of `{"prefix", "data"}` objects in
+    /// tree order.  `data` gives the value serialized for the data of an
+    /// entry.
+    pub fn write_json<W, F, T>(&self, writer: W, data: F) -> Result<(), Error>
+    where
+        W: Write,
+        F: Fn(&D) -> T,
+        T: Serialize,
+    {
+        let nodes: Vec<Rc<Node<P, D>>> = self.iter().collect();
+        write_value(writer, &entries(&nodes, &data))
+    }
+
+    /// Writes the trie as nested JSON objects with `left` and `right`
+    /// children.  Glue nodes have no `data`, and an empty table is `null`.
+    pub fn write_json_tree<W, F, T>(&self, writer: W, data: F) -> Result<(), Error>
+    where
+        W: Write,
+        F: Fn(&D) -> T,
+        T: Serialize,
+    {
+        write_value(
+            writer,
+            &self.top.as_ref().map(|top| entry(top, &data, true)),
+        )
+    }
+
+    /// Writes the longest match of `prefix` as `match`, `null` without one,
+    /// and the less specific entries covering it as `covering`, shortest
+    /// first.
+    pub fn write_lookup_json<W, F, T>(&self, writer: W, prefix: &P, data: F) -> Result<(), Error>
+    where
+        W: Write,
+        F: Fn(&D) -> T,
+        T: Serialize,
+    {
+        let mut chain = self.covering(prefix);
+        let matched = chain.pop();
+        let lookup = Lookup {
+            query: prefix.to_masked().to_string(),
+            matched: matched.as_ref().map(|node| entry(node, &data, false)),
+            covering: entries(&chain, &data),
+        };
+        write_value(writer, &lookup)
+    }
+}
+
+struct Lookup<'a, P, D, F> {
+    query: String,
+    matched: Option<Entry<'a, P, D, F>>,
+    covering: Vec<Entry<'a, P, D, F>>,
+}
+
+impl<P, D, F, T> Serialize for Lookup<'_, P, D, F>
+where
+    P: Prefix + Copy + fmt::Display,
+    F: Fn(&D) -> T,
+    T: Serialize,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut map = serializer.serialize_map(Some(3))?;
+        map.serialize_entry("query", &self.query)?;
+        map.serialize_entry("match", &self.matched)?;
+        map.serialize_entry("covering", &self.covering)?;
+        map.end()
+    }
+}
@@ -1,3 +1,43 @@
-pub use self::ptree::{Node, NodeChild, Ptree};
+pub use self::cli::{Nexthop, Route};
+pub use self::durable::Durable;
+pub use self::ecmp::NexthopGroup;
+pub use self::error::Error;
+pub use self::mrt::{MrtReader, Peer, Rib, RibEntry};
+pub use self::nht::{NexthopTracker, Notification, Resolution};
+pub use self::nlri::{Nlri, NlriReader};
+pub use self::observer::{Observer, ObserverId};
+pub use self::ptree::{Node, NodeChild, Prefix, Ptree};
+pub use self::rib::{Candidates, MultiRib};
+pub use self::set::PrefixSet;
+pub use self::snapshot::{Snapshot, SnapshotIter};
+pub use self::stats::{Stats, Structure};
+pub use self::subscribe::{Change, Subscription};
+pub use self::validate::{ValidationError, Violation};
 
+mod aggregate;
+mod cli;
+mod compress;
+mod diff;
+mod dot;
+mod durable;
+mod ecmp;
+mod error;
+mod export;
+mod file;
+mod gaps;
+#[cfg(feature = "json")]
+mod json;
+mod mrt;
+mod nht;
+mod nlri;
+mod observer;
 mod ptree;
+mod rib;
+#[cfg(feature = "serde")]
+mod serialize;
+mod set;
+mod snapshot;
+mod stats;
+mod subscribe;
+mod validate;
+mod wire;
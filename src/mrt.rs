@@ -0,0 +1,204 @@
+use std::io::{self, Read};
+use std::marker::PhantomData;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+use crate::error::Error;
+use crate::nlri::decode_prefix;
+use crate::ptree::{Prefix, Ptree};
+use crate::wire::Decoder;
+
+const TABLE_DUMP_V2: u16 = 13;
+const PEER_INDEX_TABLE: u16 = 1;
+const RIB_IPV4_UNICAST: u16 = 2;
+const RIB_IPV6_UNICAST: u16 = 4;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Peer {
+    pub bgp_id: Ipv4Addr,
+    pub addr: IpAddr,
+    pub asn: u32,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RibEntry {
+    pub peer_index: u16,
+    pub peer: Option<Peer>,
+    pub originated_time: u32,
+    pub attributes: Vec<u8>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Rib<P> {
+    pub sequence: u32,
+    pub prefix: P,
+    pub entries: Vec<RibEntry>,
+}
+
+/// Reads the RIB records of an MRT TABLE_DUMP_V2 stream (RFC 6396) for the
+/// address family of `P`.  Records of other types and families are skipped.
+pub struct MrtReader<R, P> {
+    reader: R,
+    peers: Vec<Peer>,
+    prefix: PhantomData<P>,
+}
+
+fn decode_peers(body: &[u8]) -> Result<Vec<Peer>, Error> {
+    let mut decoder = Decoder::new(body);
+    decoder.u32()?;
+    let name_len = decoder.u16()?;
+    decoder.bytes(name_len as usize)?;
+
+    let count = decoder.u16()?;
+    let mut peers = Vec::with_capacity(count as usize);
+    for _ in 0..count {
+        let peer_type = decoder.u8()?;
+        let bgp_id = Ipv4Addr::from(decoder.u32()?);
+        let addr = if peer_type & 0x01 != 0 {
+            let octets: [u8; 16] = decoder.bytes(16)?.try_into().unwrap();
+            IpAddr::V6(Ipv6Addr::from(octets))
+        } else {
+            IpAddr::V4(Ipv4Addr::from(decoder.u32()?))
+        };
+        let asn = if peer_type & 0x02 != 0 {
+            decoder.u32()?
+        } else {
+            decoder.u16()? as u32
+        };
+        peers.push(Peer { bgp_id, addr, asn });
+    }
+    Ok(peers)
+}
+
+fn decode_rib<P>(body: &[u8], peers: &[Peer]) -> Result<Rib<P>, Error>
+where
+    P: Prefix,
+{
+    let mut decoder = Decoder::new(body);
+    let sequence = decoder.u32()?;
+    let prefix = decode_prefix(&mut decoder)?;
+
+    let count = decoder.u16()?;
+    let mut entries = Vec::with_capacity(count as usize);
+    for _ in 0..count {
+        let peer_index = decoder.u16()?;
+        let originated_time = decoder.u32()?;
+        let attr_len = decoder.u16()?;
+        let attributes = decoder.bytes(attr_len as usize)?.to_vec();
+        entries.push(RibEntry {
+            peer_index,
+            peer: peers.get(peer_index as usize).cloned(),
+            originated_time,
+            attributes,
+        });
+    }
+    Ok(Rib {
+        sequence,
+        prefix,
+        entries,
+    })
+}
+
+impl<R, P> MrtReader<R, P>
+where
+    R: Read,
+    P: Prefix,
+{
+    pub fn new(reader: R) -> Self {
+        Self {
+            reader,
+            peers: Vec::new(),
+            prefix: PhantomData,
+        }
+    }
+
+    /// Peers of the last PEER_INDEX_TABLE record read.
+    pub fn peers(&self) -> &[Peer] {
+        &self.peers
+    }
+
+    // Reads the next record, returning `None` at a clean end of stream.
+    fn record(&mut self) -> Result<Option<(u16, u16, Vec<u8>)>, Error> {
+        let mut header = [0u8; 12];
+        let mut read = 0;
+        while read < header.len() {
+            match self.reader.read(&mut header[read..]) {
+                Ok(0) if read == 0 => return Ok(None),
+                Ok(0) => return Err(Error::Truncated),
+                Ok(n) => read += n,
+                Err(err) if err.kind() == io::ErrorKind::Interrupted => {}
+                Err(err) => return Err(err.into()),
+            }
+        }
+
+        let mut decoder = Decoder::new(&header);
+        decoder.u32()?;
+        let record_type = decoder.u16()?;
+        let subtype = decoder.u16()?;
+        let len = decoder.u32()?;
+
+        // The body grows as it is read, so a damaged length cannot make us
+        // allocate more than the stream holds.
+        let mut body = Vec::new();
+        (&mut self.reader).take(len as u64).read_to_end(&mut body)?;
+        if body.len() != len as usize {
+            return Err(Error::Truncated);
+        }
+        Ok(Some((record_type, subtype, body)))
+    }
+
+    fn next_rib(&mut self) -> Result<Option<Rib<P>>, Error> {
+        let rib_subtype = if P::MAX_LEN == 32 {
+            RIB_IPV4_UNICAST
+        } else {
+            RIB_IPV6_UNICAST
+        };
+        while let Some((record_type, subtype, body)) = self.record()? {
+            if record_type != TABLE_DUMP_V2 {
+                continue;
+            }
+            if subtype == PEER_INDEX_TABLE {
+                self.peers = decode_peers(&body)?;
+            } else if subtype == rib_subtype {
+                return decode_rib(&body, &self.peers).map(Some);
+            }
+        }
+        Ok(None)
+    }
+}
+
+impl<R, P> Iterator for MrtReader<R, P>
+where
+    R: Read,
+    P: Prefix,
+{
+    type Item = Result<Rib<P>, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_rib().transpose()
+    }
+}
+
+impl<P, D> Ptree<P, D>
+where
+    P: Prefix + Copy,
+{
+    /// Adds the RIB records of an MRT TABLE_DUMP_V2 stream matching the
+    /// address family of the table.  `f` maps each record, carrying all the
+    /// per-peer entries of a prefix, to the data stored or `None` to skip it.
+    /// Returns the number of prefixes added.
+    pub fn read_mrt<R, F>(&mut self, reader: R, mut f: F) -> Result<usize, Error>
+    where
+        R: Read,
+        F: FnMut(&Rib<P>) -> Option<D>,
+    {
+        let mut count = 0;
+        for rib in MrtReader::<R, P>::new(reader) {
+            let rib = rib?;
+            if let Some(data) = f(&rib) {
+                self.add(&rib.prefix, data);
+                count += 1;
+            }
+        }
+        Ok(count)
+    }
+}
@@ -0,0 +1,169 @@
+use std::collections::BTreeMap;
+
+use crate::ptree::{Prefix, Ptree};
+
+// Chains longer than this are treated as loops.
+const MAX_DEPTH: usize = 32;
+
+type Nexthop<P, D> = Box<dyn Fn(&D) -> Option<P>>;
+
+/// How a tracked address resolves.  The chains list the routes used, the
+/// first being the longest match of the address and each following one
+/// the longest match of the next hop of the previous.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Resolution<P> {
+    /// The last route of the chain has no next hop to resolve.
+    Resolved(Vec<P>),
+    /// The chain ends with a next hop no route covers.
+    Unresolved(Vec<P>),
+    /// A route of the chain is used twice or the chain is too long.
+    Loop(Vec<P>),
+}
+
+impl<P> Resolution<P> {
+    pub fn chain(&self) -> &[P] {
+        match self {
+            Resolution::Resolved(chain)
+            | Resolution::Unresolved(chain)
+            | Resolution::Loop(chain) => chain,
+        }
+    }
+
+    pub fn is_resolved(&self) -> bool {
+        matches!(self, Resolution::Resolved(_))
+    }
+}
+
+/// Change to the resolution of a tracked address.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Notification<P> {
+    pub addr: P,
+    pub old: Resolution<P>,
+    pub new: Resolution<P>,
+}
+
+struct Tracked<P> {
+    resolution: Resolution<P>,
+    // The addresses looked up to resolve, which a change has to cover to
+    // affect the resolution.
+    lookups: Vec<P>,
+}
+
+/// A table whose changes are reported for the addresses tracked in it.
+/// `nexthop` gives the next hop of a route needing recursive resolution,
+/// or `None` for a route reachable directly.
+pub struct NexthopTracker<P, D> {
+    tree: Ptree<P, D>,
+    nexthop: Nexthop<P, D>,
+    tracked: BTreeMap<P, Tracked<P>>,
+}
+
+impl<P, D> NexthopTracker<P, D>
+where
+    P: Prefix + Copy + Ord,
+{
+    pub fn new<F>(nexthop: F) -> Self
+    where
+        F: Fn(&D) -> Option<P> + 'static,
+    {
+        NexthopTracker {
+            tree: Ptree::new(),
+            nexthop: Box::new(nexthop),
+            tracked: BTreeMap::new(),
+        }
+    }
+
+    pub fn tree(&self) -> &Ptree<P, D> {
+        &self.tree
+    }
+
+    /// Starts tracking `addr`, returning its current resolution.
+    pub fn track(&mut self, addr: &P) -> Resolution<P> {
+        let addr = addr.to_masked();
+        let tracked = self.resolve(&addr);
+        let resolution = tracked.resolution.clone();
+        self.tracked.insert(addr, tracked);
+        resolution
+    }
+
+    pub fn untrack(&mut self, addr: &P) -> bool {
+        self.tracked.remove(&addr.to_masked()).is_some()
+    }
+
+    pub fn resolution(&self, addr: &P) -> Option<&Resolution<P>> {
+        self.tracked
+            .get(&addr.to_masked())
+            .map(|tracked| &tracked.resolution)
+    }
+
+    /// Adds a route, returning the notifications for the tracked addresses
+    /// now resolving differently or through the new route.
+    pub fn add(&mut self, prefix: &P, data: D) -> Vec<Notification<P>> {
+        self.tree.add(prefix, data);
+        self.update(&prefix.to_masked())
+    }
+
+    pub fn delete(&mut self, prefix: &P) -> Vec<Notification<P>> {
+        self.tree.delete(prefix);
+        self.update(&prefix.to_masked())
+    }
+
+    fn update(&mut self, prefix: &P) -> Vec<Notification<P>> {
+        let affected: Vec<P> = self
+            .tracked
+            .iter()
+            .filter(|(_, tracked)| tracked.lookups.iter().any(|addr| prefix.contains(addr)))
+            .map(|(addr, _)| *addr)
+            .collect();
+
+        let mut notifications = Vec::new();
+        for addr in affected {
+            let tracked = self.resolve(&addr);
+            let old = self.tracked.insert(addr, tracked).unwrap().resolution;
+            let new = &self.tracked[&addr].resolution;
+            if old != *new || new.chain().contains(prefix) {
+                notifications.push(Notification {
+                    addr,
+                    old,
+                    new: new.clone(),
+                });
+            }
+        }
+        notifications
+    }
+
+    fn resolve(&self, addr: &P) -> Tracked<P> {
+        let mut chain: Vec<P> = Vec::new();
+        let mut lookups = vec![*addr];
+        loop {
+            let Some(node) = self.tree.lookup(lookups.last().unwrap()).node else {
+                return Tracked {
+                    resolution: Resolution::Unresolved(chain),
+                    lookups,
+                };
+            };
+            if chain.contains(&node.prefix) || chain.len() == MAX_DEPTH {
+                chain.push(node.prefix);
+                return Tracked {
+                    resolution: Resolution::Loop(chain),
+                    lookups,
+                };
+            }
+            chain.push(node.prefix);
+            let nexthop = node
+                .data
+                .borrow()
+                .as_ref()
+                .and_then(|data| (self.nexthop)(data));
+            match nexthop {
+                Some(nexthop) => lookups.push(nexthop.to_masked()),
+                None => {
+                    return Tracked {
+                        resolution: Resolution::Resolved(chain),
+                        lookups,
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,120 @@
+use std::marker::PhantomData;
+
+use crate::error::Error;
+use crate::ptree::{Prefix, Ptree};
+use crate::wire::Decoder;
+
+/// A prefix as carried in the NLRI and withdrawn routes fields of a BGP
+/// UPDATE, with the path identifier of ADD-PATH (RFC 7911) if negotiated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Nlri<P> {
+    pub path_id: Option<u32>,
+    pub prefix: P,
+}
+
+impl<P> Nlri<P>
+where
+    P: Prefix,
+{
+    /// Appends the path identifier if any, the prefix length and the
+    /// octets needed for that length (RFC 4271 section 4.3).
+    pub fn encode(&self, out: &mut Vec<u8>) {
+        if let Some(path_id) = self.path_id {
+            out.extend_from_slice(&path_id.to_be_bytes());
+        }
+        let prefix = self.prefix.to_masked();
+        let len = prefix.prefix_len();
+        out.push(len);
+        out.extend_from_slice(&prefix.octets()[..len.div_ceil(8) as usize]);
+    }
+}
+
+pub(crate) fn decode_prefix<P>(decoder: &mut Decoder) -> Result<P, Error>
+where
+    P: Prefix,
+{
+    let len = decoder.u8()?;
+    if len > P::MAX_LEN {
+        return Err(Error::InvalidPrefixLength(len));
+    }
+    let octets = decoder.bytes(len.div_ceil(8) as usize)?;
+    P::from_octets(octets, len).ok_or(Error::InvalidPrefixLength(len))
+}
+
+/// Iterator over the prefixes packed in an NLRI field.  Iteration ends
+/// after the first error.
+pub struct NlriReader<'a, P> {
+    decoder: Decoder<'a>,
+    add_path: bool,
+    failed: bool,
+    _prefix: PhantomData<P>,
+}
+
+impl<'a, P> NlriReader<'a, P> {
+    pub fn new(bytes: &'a [u8], add_path: bool) -> Self {
+        NlriReader {
+            decoder: Decoder::new(bytes),
+            add_path,
+            failed: false,
+            _prefix: PhantomData,
+        }
+    }
+}
+
+impl<P> Iterator for NlriReader<'_, P>
+where
+    P: Prefix,
+{
+    type Item = Result<Nlri<P>, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed || self.decoder.len() == 0 {
+            return None;
+        }
+        let mut decode = || {
+            let path_id = if self.add_path {
+                Some(self.decoder.u32()?)
+            } else {
+                None
+            };
+            let prefix = decode_prefix(&mut self.decoder)?;
+            Ok(Nlri { path_id, prefix })
+        };
+        let nlri = decode();
+        self.failed = nlri.is_err();
+        Some(nlri)
+    }
+}
+
+impl<P, D> Ptree<P, D>
+where
+    P: Prefix + Copy,
+{
+    /// Applies the withdrawn routes and NLRI fields of an UPDATE, deleting
+    /// the withdrawn prefixes and then adding the announced ones with the
+    /// data returned by `f`.  Nothing is changed unless both fields decode
+    /// cleanly.  Returns the numbers of withdrawn and announced prefixes.
+    ///
+    /// The table keeps one entry per prefix, so a withdrawal removes the
+    /// prefix whatever its path identifier.
+    pub fn apply_nlri<F>(
+        &mut self,
+        withdrawn: &[u8],
+        announced: &[u8],
+        add_path: bool,
+        mut f: F,
+    ) -> Result<(usize, usize), Error>
+    where
+        F: FnMut(&Nlri<P>) -> D,
+    {
+        let withdrawn = NlriReader::<P>::new(withdrawn, add_path).collect::<Result<Vec<_>, _>>()?;
+        let announced = NlriReader::<P>::new(announced, add_path).collect::<Result<Vec<_>, _>>()?;
+        for nlri in withdrawn.iter() {
+            self.delete(&nlri.prefix);
+        }
+        for nlri in announced.iter() {
+            self.add(&nlri.prefix, f(nlri));
+        }
+        Ok((withdrawn.len(), announced.len()))
+    }
+}
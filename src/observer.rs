@@ -0,0 +1,69 @@
+use std::rc::Rc;
+
+use crate::ptree::{Node, Prefix, Ptree};
+
+/// Receives the changes made to a table by `add()`, `try_add()` and
+/// `delete()`, and by the operations built on them.  Each of these changes
+/// is reported exactly once.  Data changed on a node directly, through
+/// `Node::set_data()`, `Node::unset_data()` or `Node::data`, bypasses the
+/// table and is not reported.
+pub trait Observer<P, D> {
+    fn added(&mut self, _prefix: &P, _data: &D) {}
+    fn replaced(&mut self, _prefix: &P, _old: &D, _new: &D) {}
+    fn removed(&mut self, _prefix: &P, _data: &D) {}
+
+    /// Returns true once the observer wants no more changes, so that it is
+    /// removed before the next one is reported.
+    fn is_closed(&self) -> bool {
+        false
+    }
+}
+
+/// Handle returned by `Ptree::add_observer()` to remove the observer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ObserverId(usize);
+
+pub(crate) type Observers<P, D> = Vec<(ObserverId, Box<dyn Observer<P, D>>)>;
+
+impl<P, D> Ptree<P, D>
+where
+    P: Prefix + Copy,
+{
+    pub fn add_observer(&mut self, observer: Box<dyn Observer<P, D>>) -> ObserverId {
+        let id = ObserverId(self.next_observer);
+        self.next_observer += 1;
+        self.observers.push((id, observer));
+        id
+    }
+
+    /// Removes an observer, returning it if it was registered.
+    pub fn remove_observer(&mut self, id: ObserverId) -> Option<Box<dyn Observer<P, D>>> {
+        let index = self.observers.iter().position(|(i, _)| *i == id)?;
+        Some(self.observers.remove(index).1)
+    }
+
+    fn prune_observers(&mut self) {
+        self.observers.retain(|(_, observer)| !observer.is_closed());
+    }
+
+    pub(crate) fn notify_add(&mut self, node: &Rc<Node<P, D>>, old: Option<D>) {
+        self.prune_observers();
+        let data = node.data.borrow();
+        let Some(new) = data.as_ref() else {
+            return;
+        };
+        for (_, observer) in self.observers.iter_mut() {
+            match old.as_ref() {
+                Some(old) => observer.replaced(&node.prefix, old, new),
+                None => observer.added(&node.prefix, new),
+            }
+        }
+    }
+
+    pub(crate) fn notify_removed(&mut self, prefix: &P, data: &D) {
+        self.prune_observers();
+        for (_, observer) in self.observers.iter_mut() {
+            observer.removed(prefix, data);
+        }
+    }
+}
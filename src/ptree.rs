@@ -1,8 +1,13 @@
 use std::cell::RefCell;
-use std::net::Ipv4Addr;
+use std::fmt;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
 use std::rc::Rc;
+use std::str::FromStr;
 
-use ipnet::Ipv4Net;
+use ipnet::{IpNet, Ipv4Net, Ipv6Net};
+
+use crate::error::Error;
+use crate::observer::Observers;
 
 const MASK_BITS: [u8; 9] = [0x00, 0x80, 0xc0, 0xe0, 0xf0, 0xf8, 0xfc, 0xfe, 0xff];
 
@@ -43,14 +48,32 @@ const IPV4_MASK: [[u8; 4]; 33] = [
 ];
 
 pub trait Prefix {
+    const MAX_LEN: u8;
+
     fn to_masked(&self) -> Self;
     fn contains(&self, prefix: &Self) -> bool;
     fn bit_at(&self, index: u8) -> u8;
     fn from_common(prefix1: &Self, prefix2: &Self) -> Self;
     fn prefix_len(&self) -> u8;
+    fn child(&self, bit: u8) -> Self;
+    fn default_route() -> Self;
+    fn from_octets(octets: &[u8], len: u8) -> Option<Self>
+    where
+        Self: Sized;
+    fn octets(&self) -> Vec<u8>;
+
+    /// Number of addresses in the prefix, capped at `u128::MAX` as the IPv6
+    /// default route holds one more than that.
+    fn address_count(&self) -> u128 {
+        1u128
+            .checked_shl((Self::MAX_LEN - self.prefix_len()) as u32)
+            .unwrap_or(u128::MAX)
+    }
 }
 
 impl Prefix for Ipv4Net {
+    const MAX_LEN: u8 = 32;
+
     fn prefix_len(&self) -> u8 {
         self.prefix_len()
     }
@@ -72,8 +95,10 @@ impl Prefix for Ipv4Net {
         let octets2: [u8; 4] = prefix2.addr().octets();
         let mut octets: [u8; 4] = [0; 4];
 
+        let len = prefix1.prefix_len().min(prefix2.prefix_len());
+
         let mut i: usize = 0;
-        while i < prefix2.prefix_len() as usize / 8 {
+        while i < len as usize / 8 {
             if octets1[i] == octets2[i] {
                 octets[i] = octets1[i];
             } else {
@@ -84,10 +109,10 @@ impl Prefix for Ipv4Net {
 
         let mut prefixlen = (i * 8) as u8;
 
-        if prefixlen != prefix2.prefix_len() {
+        if prefixlen != len {
             let diff = octets1[i] ^ octets2[i];
             let mut mask = 0x80u8;
-            while prefixlen < prefix2.prefix_len() && (mask & diff) == 0 {
+            while prefixlen < len && (mask & diff) == 0 {
                 mask >>= 1;
                 prefixlen += 1;
             }
@@ -110,6 +135,32 @@ impl Prefix for Ipv4Net {
         (octets[offset as usize] >> shift) & 0x1
     }
 
+    fn child(&self, bit: u8) -> Self {
+        let len = self.prefix_len();
+        let mut addr = u32::from(self.addr());
+        if bit != 0 {
+            addr |= 1 << (31 - len);
+        }
+        Ipv4Net::new(Ipv4Addr::from(addr), len + 1).unwrap()
+    }
+
+    fn default_route() -> Self {
+        Ipv4Net::default()
+    }
+
+    fn from_octets(octets: &[u8], len: u8) -> Option<Self> {
+        let mut addr = [0u8; 4];
+        let size = octets.len().min(4);
+        addr[..size].copy_from_slice(&octets[..size]);
+        Ipv4Net::new(Ipv4Addr::from(addr), len)
+            .ok()
+            .map(|prefix| prefix.to_masked())
+    }
+
+    fn octets(&self) -> Vec<u8> {
+        self.network().octets().to_vec()
+    }
+
     fn contains(&self, prefix: &Self) -> bool {
         if self.prefix_len() > prefix.prefix_len() {
             return false;
@@ -136,14 +187,102 @@ impl Prefix for Ipv4Net {
     }
 }
 
+fn ipv6_mask(len: u8) -> u128 {
+    u128::MAX.checked_shl(128 - len as u32).unwrap_or(0)
+}
+
+impl Prefix for Ipv6Net {
+    const MAX_LEN: u8 = 128;
+
+    fn prefix_len(&self) -> u8 {
+        self.prefix_len()
+    }
+
+    fn to_masked(&self) -> Self {
+        let addr = u128::from(self.addr()) & ipv6_mask(self.prefix_len());
+        Ipv6Net::new(Ipv6Addr::from(addr), self.prefix_len()).unwrap()
+    }
+
+    fn from_common(prefix1: &Self, prefix2: &Self) -> Self {
+        let addr1 = u128::from(prefix1.addr());
+        let addr2 = u128::from(prefix2.addr());
+        let len = prefix1.prefix_len().min(prefix2.prefix_len());
+        let prefixlen = ((addr1 ^ addr2).leading_zeros() as u8).min(len);
+
+        Ipv6Net::new(Ipv6Addr::from(addr1 & ipv6_mask(prefixlen)), prefixlen).unwrap()
+    }
+
+    fn bit_at(&self, index: u8) -> u8 {
+        ((u128::from(self.addr()) >> (127 - index)) & 0x1) as u8
+    }
+
+    fn child(&self, bit: u8) -> Self {
+        let len = self.prefix_len();
+        let mut addr = u128::from(self.addr());
+        if bit != 0 {
+            addr |= 1 << (127 - len);
+        }
+        Ipv6Net::new(Ipv6Addr::from(addr), len + 1).unwrap()
+    }
+
+    fn default_route() -> Self {
+        Ipv6Net::default()
+    }
+
+    fn from_octets(octets: &[u8], len: u8) -> Option<Self> {
+        let mut addr = [0u8; 16];
+        let size = octets.len().min(16);
+        addr[..size].copy_from_slice(&octets[..size]);
+        Ipv6Net::new(Ipv6Addr::from(addr), len)
+            .ok()
+            .map(|prefix| prefix.to_masked())
+    }
+
+    fn octets(&self) -> Vec<u8> {
+        self.network().octets().to_vec()
+    }
+
+    fn contains(&self, prefix: &Self) -> bool {
+        if self.prefix_len() > prefix.prefix_len() {
+            return false;
+        }
+
+        let mask = ipv6_mask(self.prefix_len());
+        (u128::from(self.addr()) ^ u128::from(prefix.addr())) & mask == 0
+    }
+}
+
 #[derive(Debug)]
 pub struct Node<P, D> {
     pub prefix: P,
     pub data: RefCell<Option<D>>,
     pub parent: RefCell<Option<Rc<Node<P, D>>>>,
+    #[allow(clippy::type_complexity)]
     pub children: [RefCell<Option<Rc<Node<P, D>>>>; 2],
 }
 
+// Parses a prefix, taking a bare address as a host prefix.
+pub(crate) fn parse_prefix<P>(str: &str) -> Result<P, Error>
+where
+    P: Prefix + FromStr,
+{
+    if let Ok(prefix) = str.parse::<P>() {
+        return Ok(prefix);
+    }
+    let net = match str.parse::<IpAddr>() {
+        Ok(addr) => IpNet::from(addr),
+        Err(_) => str
+            .parse::<IpNet>()
+            .map_err(|_| Error::Parse(str.to_string()))?,
+    };
+    if net.max_prefix_len() != P::MAX_LEN {
+        return Err(Error::AddressFamilyMismatch(str.to_string()));
+    }
+    net.to_string()
+        .parse::<P>()
+        .map_err(|_| Error::Parse(str.to_string()))
+}
+
 fn node_match_prefix<P, D>(node: Option<Rc<Node<P, D>>>, prefix: &P) -> bool
 where
     P: Prefix,
@@ -165,14 +304,46 @@ where
     child.set_parent(parent.clone());
 }
 
-#[derive(Debug)]
 pub struct Ptree<P, D> {
-    top: Option<Rc<Node<P, D>>>,
+    pub(crate) top: Option<Rc<Node<P, D>>>,
+    strict: bool,
+    pub(crate) observers: Observers<P, D>,
+    pub(crate) next_observer: usize,
+}
+
+impl<P, D> fmt::Debug for Ptree<P, D>
+where
+    P: fmt::Debug,
+    D: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Ptree")
+            .field("top", &self.top)
+            .field("strict", &self.strict)
+            .field("observers", &self.observers.len())
+            .finish()
+    }
 }
 
 impl<D> Ptree<Ipv4Net, D> {
     pub fn new_ipv4() -> Self {
-        Self { top: None }
+        Self {
+            top: None,
+            strict: false,
+            observers: Vec::new(),
+            next_observer: 0,
+        }
+    }
+}
+
+impl<D> Ptree<Ipv6Net, D> {
+    pub fn new_ipv6() -> Self {
+        Self {
+            top: None,
+            strict: false,
+            observers: Vec::new(),
+            next_observer: 0,
+        }
     }
 }
 
@@ -181,10 +352,16 @@ where
     P: Prefix + Copy,
 {
     pub fn new() -> Self {
-        Self { top: None }
+        Self {
+            top: None,
+            strict: false,
+            observers: Vec::new(),
+            next_observer: 0,
+        }
     }
 
     pub fn insert(&mut self, prefix: &P) -> NodeIter<P, D> {
+        let prefix = &prefix.to_masked();
         let mut cursor = self.top.clone();
         let mut matched: Option<Rc<Node<P, D>>> = None;
         let mut new_node: Rc<Node<P, D>>;
@@ -234,6 +411,7 @@ where
     }
 
     pub fn lookup(&self, prefix: &P) -> NodeIter<P, D> {
+        let prefix = &prefix.to_masked();
         let mut cursor = self.top.clone();
         let mut matched: Option<Rc<Node<P, D>>> = None;
 
@@ -256,7 +434,28 @@ where
         }
     }
 
+    /// Returns the entries containing `prefix`, shortest first, so that the
+    /// last one is the longest match.
+    pub fn covering(&self, prefix: &P) -> Vec<Rc<Node<P, D>>> {
+        let prefix = &prefix.to_masked();
+        let mut cursor = self.top.clone();
+        let mut chain = Vec::new();
+
+        while node_match_prefix(cursor.clone(), prefix) {
+            let node = cursor.clone().unwrap();
+            if node.has_data() {
+                chain.push(node.clone());
+            }
+            if node.prefix.prefix_len() == prefix.prefix_len() {
+                break;
+            }
+            cursor = node.child_with(prefix.bit_at(node.prefix.prefix_len()));
+        }
+        chain
+    }
+
     pub fn lookup_exact(&self, prefix: &P) -> NodeIter<P, D> {
+        let prefix = &prefix.to_masked();
         let mut cursor = self.top.clone();
 
         while node_match_prefix(cursor.clone(), prefix) {
@@ -275,6 +474,7 @@ where
     }
 
     pub fn find(&self, prefix: &P) -> NodeIter<P, D> {
+        let prefix = &prefix.to_masked();
         let mut cursor = self.top.clone();
 
         while node_match_prefix(cursor.clone(), prefix) {
@@ -288,6 +488,25 @@ where
         NodeIter { node: None }
     }
 
+    // Returns the topmost node contained in `prefix`.
+    pub(crate) fn subtree(&self, prefix: &P) -> Option<Rc<Node<P, D>>> {
+        let mut cursor = self.top.clone();
+
+        while let Some(node) = cursor {
+            if prefix.prefix_len() <= node.prefix.prefix_len() {
+                if prefix.contains(&node.prefix) {
+                    return Some(node);
+                }
+                break;
+            }
+            if !node.prefix.contains(prefix) {
+                break;
+            }
+            cursor = node.child_with(prefix.bit_at(node.prefix.prefix_len()));
+        }
+        None
+    }
+
     fn erase(&mut self, iter: NodeIter<P, D>) {
         if let Some(node) = iter.node {
             let has_left = node.child(NodeChild::Left).is_some();
@@ -321,7 +540,8 @@ where
                         parent.children[NodeChild::Right as usize].replace(child.clone());
                     }
                 }
-                if !parent.is_occupied() {
+                // A glue node left with a single child is spliced out too.
+                if !parent.is_needed() {
                     self.erase(NodeIter::from_node(parent));
                 }
             } else {
@@ -333,13 +553,52 @@ where
     pub fn add(&mut self, prefix: &P, data: D) {
         let it = self.insert(prefix);
         if let Some(node) = it.node {
-            node.set_data(data);
+            let old = node.data.replace(Some(data));
+            self.notify_add(&node, old);
         }
     }
 
+    /// Same as `add()`, but in strict mode a prefix with host bits set is
+    /// rejected instead of being masked.
+    pub fn try_add(&mut self, prefix: &P, data: D) -> Result<(), Error>
+    where
+        P: PartialEq + fmt::Display,
+    {
+        if self.strict && prefix.to_masked() != *prefix {
+            return Err(Error::HostBitsSet(prefix.to_string()));
+        }
+        self.add(prefix, data);
+        Ok(())
+    }
+
     pub fn delete(&mut self, prefix: &P) {
         let iter = self.lookup_exact(prefix);
+        let removed = iter
+            .node
+            .as_ref()
+            .and_then(|node| Some((node.prefix, node.data.replace(None)?)));
         self.erase(iter);
+        if let Some((prefix, data)) = removed {
+            self.notify_removed(&prefix, &data);
+        }
+    }
+
+    pub fn set_strict(&mut self, strict: bool) {
+        self.strict = strict;
+    }
+
+    pub fn is_strict(&self) -> bool {
+        self.strict
+    }
+
+    /// Number of entries.  Data can be set on nodes directly, so they are
+    /// counted by walking the tree rather than kept in a counter.
+    pub fn len(&self) -> usize {
+        self.iter().count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.iter().next().is_none()
     }
 
     pub fn node_iter(&self) -> NodeIter<P, D> {
@@ -354,33 +613,86 @@ where
         }
     }
 
-    // pub fn route_ipv4_add(&mut self, str: &str, data: D) {
-    //     let prefix: P = str.parse().unwrap();
-    //     self.add(&prefix, data);
-    // }
+    /// Iterates over the entries contained in `prefix`.
+    pub fn iter_within(&self, prefix: &P) -> impl Iterator<Item = Rc<Node<P, D>>> {
+        let prefix = prefix.to_masked();
+        DataIter {
+            node: self.subtree(&prefix),
+        }
+        .take_while(move |node| prefix.contains(&node.prefix))
+    }
 
-    // pub fn route_ipv4_delete(&mut self, str: &str) {
-    //     let prefix: Ipv4Net = str.parse().unwrap();
-    //     self.delete(&prefix);
-    // }
+    pub fn add_str(&mut self, str: &str, data: D) -> Result<(), Error>
+    where
+        P: FromStr + PartialEq + fmt::Display,
+    {
+        let prefix: P = parse_prefix(str)?;
+        self.try_add(&prefix, data)
+    }
 
-    // pub fn route_ipv4_lookup(&self, str: &str) -> Option<Rc<Node<P, D>>> {
-    //     let prefix: Ipv4Net = str.parse().unwrap();
-    //     let iter = self.lookup(&prefix);
-    //     iter.node
-    // }
+    pub fn delete_str(&mut self, str: &str) -> Result<(), Error>
+    where
+        P: FromStr,
+    {
+        let prefix: P = parse_prefix(str)?;
+        self.delete(&prefix);
+        Ok(())
+    }
 
-    // pub fn route_ipv4_lookup_exact(&self, str: &str) -> Option<Rc<Node<P, D>>> {
-    //     let prefix: Ipv4Net = str.parse().unwrap();
-    //     let iter = self.lookup_exact(&prefix);
-    //     iter.node
-    // }
+    pub fn lookup_str(&self, str: &str) -> Result<Option<Rc<Node<P, D>>>, Error>
+    where
+        P: FromStr,
+    {
+        let prefix: P = parse_prefix(str)?;
+        Ok(self.lookup(&prefix).node)
+    }
+
+    pub fn lookup_exact_str(&self, str: &str) -> Result<Option<Rc<Node<P, D>>>, Error>
+    where
+        P: FromStr,
+    {
+        let prefix: P = parse_prefix(str)?;
+        Ok(self.lookup_exact(&prefix).node)
+    }
 
-    // pub fn route_ipv4_find(&self, str: &str) -> Option<Rc<Node<P, D>>> {
-    //     let prefix: Ipv4Net = str.parse().unwrap();
-    //     let iter = self.find(&prefix);
-    //     iter.node
-    // }
+    pub fn find_str(&self, str: &str) -> Result<Option<Rc<Node<P, D>>>, Error>
+    where
+        P: FromStr,
+    {
+        let prefix: P = parse_prefix(str)?;
+        Ok(self.find(&prefix).node)
+    }
+}
+
+impl<P, D> Default for Ptree<P, D>
+where
+    P: Prefix + Copy,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<P, D> FromIterator<(P, D)> for Ptree<P, D>
+where
+    P: Prefix + Copy,
+{
+    fn from_iter<I: IntoIterator<Item = (P, D)>>(iter: I) -> Self {
+        let mut ptree = Ptree::new();
+        ptree.extend(iter);
+        ptree
+    }
+}
+
+impl<P, D> Extend<(P, D)> for Ptree<P, D>
+where
+    P: Prefix + Copy,
+{
+    fn extend<I: IntoIterator<Item = (P, D)>>(&mut self, iter: I) {
+        for (prefix, data) in iter {
+            self.add(&prefix, data);
+        }
+    }
 }
 
 impl<P, D> Drop for Node<P, D> {
@@ -420,7 +732,7 @@ where
         Self::new(&common)
     }
 
-    fn child_with(&self, bit: u8) -> Option<Rc<Node<P, D>>> {
+    pub(crate) fn child_with(&self, bit: u8) -> Option<Rc<Node<P, D>>> {
         self.children[bit as usize].borrow().clone()
     }
 
@@ -440,7 +752,7 @@ where
         self.data.replace(None);
     }
 
-    fn has_data(&self) -> bool {
+    pub(crate) fn has_data(&self) -> bool {
         self.data.borrow().is_some()
     }
 
@@ -452,8 +764,9 @@ where
         self.children[NodeChild::Right as usize].borrow().is_some()
     }
 
-    fn is_occupied(&self) -> bool {
-        self.has_data() || self.has_left() || self.has_right()
+    // A node without data is only needed to join two branches.
+    fn is_needed(&self) -> bool {
+        self.has_data() || (self.has_left() && self.has_right())
     }
 
     fn eq(lhs: &Self, rhs: &Self) -> bool {
@@ -610,6 +923,27 @@ mod tests {
         assert!(!net10_8.contains(&net127_8));
     }
 
+    #[test]
+    pub fn test_ipv6() {
+        let net: Ipv6Net = "2001:db8:ffff::1/33".parse().unwrap();
+        assert_eq!(net.to_masked(), "2001:db8:8000::/33".parse().unwrap());
+
+        let net32: Ipv6Net = "2001:db8::/32".parse().unwrap();
+        let net48: Ipv6Net = "2001:db8:1::/48".parse().unwrap();
+        let other: Ipv6Net = "2001:db9::/32".parse().unwrap();
+        assert!(net32.contains(&net48));
+        assert!(!net48.contains(&net32));
+        assert!(!net32.contains(&other));
+
+        assert_eq!(Ipv6Net::from_common(&net48, &net32), net32);
+        assert_eq!(
+            Ipv6Net::from_common(&net32, &other),
+            "2001:db8::/31".parse().unwrap()
+        );
+        assert_eq!(net32.child(1), "2001:db8:8000::/33".parse().unwrap());
+        assert_eq!(net48.bit_at(47), 1);
+    }
+
     #[test]
     pub fn test_generics() {
         let mut ptree = Ptree::<Ipv4Net, i32>::new();
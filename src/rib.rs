@@ -0,0 +1,185 @@
+use std::cmp::Ordering;
+
+use crate::cli::Route;
+use crate::ptree::{Prefix, Ptree};
+
+type Compare<R> = Box<dyn Fn(&R, &R) -> Ordering>;
+
+/// The routes of a prefix keyed by source, best first.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Candidates<S, R> {
+    routes: Vec<(S, R)>,
+}
+
+impl<S, R> Candidates<S, R> {
+    pub fn best(&self) -> Option<(&S, &R)> {
+        self.routes.first().map(|(source, route)| (source, route))
+    }
+
+    pub fn get(&self, source: &S) -> Option<&R>
+    where
+        S: PartialEq,
+    {
+        self.routes
+            .iter()
+            .find(|(s, _)| s == source)
+            .map(|(_, route)| route)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&S, &R)> {
+        self.routes.iter().map(|(source, route)| (source, route))
+    }
+
+    pub fn len(&self) -> usize {
+        self.routes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.routes.is_empty()
+    }
+
+    fn remove(&mut self, source: &S) -> Option<R>
+    where
+        S: PartialEq,
+    {
+        let index = self.routes.iter().position(|(s, _)| s == source)?;
+        Some(self.routes.remove(index).1)
+    }
+}
+
+/// A table holding for each prefix the routes learned from several
+/// sources, where `compare` orders routes with the preferred one as the
+/// lesser.  Equally preferred routes are ordered by source.
+pub struct MultiRib<P, S, R> {
+    tree: Ptree<P, Candidates<S, R>>,
+    compare: Compare<R>,
+}
+
+impl<P, S, R> MultiRib<P, S, R>
+where
+    P: Prefix + Copy,
+    S: Ord + Clone,
+    R: Clone,
+{
+    pub fn new<F>(compare: F) -> Self
+    where
+        F: Fn(&R, &R) -> Ordering + 'static,
+    {
+        MultiRib {
+            tree: Ptree::new(),
+            compare: Box::new(compare),
+        }
+    }
+
+    /// The underlying table, for iterating over prefixes and candidates.
+    pub fn tree(&self) -> &Ptree<P, Candidates<S, R>> {
+        &self.tree
+    }
+
+    /// Adds or replaces the route of `source` for `prefix`, returning the
+    /// route replaced.
+    pub fn insert(&mut self, prefix: &P, source: S, route: R) -> Option<R> {
+        let Some(node) = self.tree.lookup_exact(prefix).node else {
+            self.tree.add(
+                prefix,
+                Candidates {
+                    routes: vec![(source, route)],
+                },
+            );
+            return None;
+        };
+        let mut data = node.data.borrow_mut();
+        let candidates = data.as_mut().unwrap();
+        let replaced = candidates.remove(&source);
+        let index = candidates.routes.partition_point(|(s, r)| {
+            (self.compare)(r, &route).then_with(|| s.cmp(&source)) == Ordering::Less
+        });
+        candidates.routes.insert(index, (source, route));
+        replaced
+    }
+
+    /// Removes the route of `source` for `prefix`, and the prefix with its
+    /// last route.
+    pub fn remove(&mut self, prefix: &P, source: &S) -> Option<R> {
+        let node = self.tree.lookup_exact(prefix).node?;
+        let (removed, empty) = {
+            let mut data = node.data.borrow_mut();
+            let candidates = data.as_mut().unwrap();
+            (candidates.remove(source), candidates.is_empty())
+        };
+        if empty {
+            self.tree.delete(prefix);
+        }
+        removed
+    }
+
+    /// Removes all routes of `source`, as when a peer goes down.  Returns
+    /// the number of routes removed.
+    pub fn remove_source(&mut self, source: &S) -> usize {
+        let prefixes: Vec<P> = self
+            .tree
+            .iter()
+            .filter(|node| {
+                node.data
+                    .borrow()
+                    .as_ref()
+                    .is_some_and(|c| c.get(source).is_some())
+            })
+            .map(|node| node.prefix)
+            .collect();
+        for prefix in prefixes.iter() {
+            self.remove(prefix, source);
+        }
+        prefixes.len()
+    }
+
+    /// Longest match, returning the matching prefix and its best route.
+    pub fn lookup(&self, prefix: &P) -> Option<(P, R)> {
+        let node = self.tree.lookup(prefix).node?;
+        let data = node.data.borrow();
+        let (_, route) = data.as_ref()?.best()?;
+        Some((node.prefix, route.clone()))
+    }
+
+    pub fn lookup_exact(&self, prefix: &P) -> Option<R> {
+        let node = self.tree.lookup_exact(prefix).node?;
+        let data = node.data.borrow();
+        data.as_ref()?.best().map(|(_, route)| route.clone())
+    }
+
+    /// All routes for exactly `prefix`, best first.
+    pub fn candidates(&self, prefix: &P) -> Vec<(S, R)> {
+        match self.tree.lookup_exact(prefix).node {
+            Some(node) => node
+                .data
+                .borrow()
+                .as_ref()
+                .map(|c| c.routes.clone())
+                .unwrap_or_default(),
+            None => Vec::new(),
+        }
+    }
+
+    /// Number of prefixes.
+    pub fn len(&self) -> usize {
+        self.tree.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tree.is_empty()
+    }
+}
+
+impl Route {
+    /// Orders routes by administrative distance and then metric, lower
+    /// being preferred and unknown values last.
+    pub fn preference(&self, other: &Route) -> Ordering {
+        let key = |route: &Route| {
+            (
+                route.distance.unwrap_or(u32::MAX),
+                route.metric.unwrap_or(u32::MAX),
+            )
+        };
+        key(self).cmp(&key(other))
+    }
+}
@@ -0,0 +1,67 @@
+use std::fmt;
+use std::marker::PhantomData;
+
+use serde::de::{Deserialize, Deserializer, SeqAccess, Visitor};
+use serde::ser::{Serialize, SerializeSeq, Serializer};
+
+use crate::ptree::{Prefix, Ptree};
+
+// Entries are serialized as a sequence of (prefix, data) pairs in tree
+// order, so glue nodes never reach the output.
+impl<P, D> Serialize for Ptree<P, D>
+where
+    P: Prefix + Copy + Serialize,
+    D: Serialize,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let nodes = self.iter().collect::<Vec<_>>();
+        let mut seq = serializer.serialize_seq(Some(nodes.len()))?;
+        for node in nodes {
+            if let Some(data) = node.data.borrow().as_ref() {
+                seq.serialize_element(&(node.prefix, data))?;
+            }
+        }
+        seq.end()
+    }
+}
+
+struct PtreeVisitor<P, D>(PhantomData<(P, D)>);
+
+impl<'de, P, D> Visitor<'de> for PtreeVisitor<P, D>
+where
+    P: Prefix + Copy + Deserialize<'de>,
+    D: Deserialize<'de>,
+{
+    type Value = Ptree<P, D>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a sequence of (prefix, data) pairs")
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let mut ptree = Ptree::new();
+        while let Some((prefix, data)) = seq.next_element::<(P, D)>()? {
+            ptree.add(&prefix, data);
+        }
+        Ok(ptree)
+    }
+}
+
+impl<'de, P, D> Deserialize<'de> for Ptree<P, D>
+where
+    P: Prefix + Copy + Deserialize<'de>,
+    D: Deserialize<'de>,
+{
+    fn deserialize<De>(deserializer: De) -> Result<Self, De::Error>
+    where
+        De: Deserializer<'de>,
+    {
+        deserializer.deserialize_seq(PtreeVisitor(PhantomData))
+    }
+}
@@ -0,0 +1,134 @@
+use std::net::Ipv4Addr;
+
+use ipnet::Ipv4Net;
+
+use crate::diff::cover_all;
+use crate::ptree::{Prefix, Ptree};
+
+/// A set of addresses kept as the minimal list of covering prefixes.
+#[derive(Debug)]
+pub struct PrefixSet<P> {
+    tree: Ptree<P, ()>,
+}
+
+impl<P> PrefixSet<P>
+where
+    P: Prefix + Copy,
+{
+    pub fn new() -> Self {
+        Self { tree: Ptree::new() }
+    }
+
+    fn from_cover(prefixes: Vec<P>) -> Self {
+        let mut tree = Ptree::new();
+        for prefix in prefixes.iter() {
+            tree.add(prefix, ());
+        }
+        Self { tree }
+    }
+
+    fn combine<F>(&self, other: &Self, f: F) -> Self
+    where
+        F: Fn(bool, bool) -> bool,
+    {
+        Self::from_cover(cover_all(&self.tree, &other.tree, &|a, b| {
+            f(a.is_some(), b.is_some())
+        }))
+    }
+
+    fn single(prefix: &P) -> Self {
+        Self::from_cover(vec![prefix.to_masked()])
+    }
+
+    pub fn insert(&mut self, prefix: &P) {
+        if !self.contains(prefix) {
+            *self = self.union(&Self::single(prefix));
+        }
+    }
+
+    pub fn remove(&mut self, prefix: &P) {
+        *self = self.difference(&Self::single(prefix));
+    }
+
+    /// Returns true when every address of `prefix` is in the set.
+    pub fn contains(&self, prefix: &P) -> bool {
+        self.tree.lookup(&prefix.to_masked()).node.is_some()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tree.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.tree.len()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = P> {
+        self.tree.iter().map(|node| node.prefix)
+    }
+
+    pub fn union(&self, other: &Self) -> Self {
+        self.combine(other, |a, b| a || b)
+    }
+
+    pub fn intersection(&self, other: &Self) -> Self {
+        self.combine(other, |a, b| a && b)
+    }
+
+    pub fn difference(&self, other: &Self) -> Self {
+        self.combine(other, |a, b| a && !b)
+    }
+
+    pub fn symmetric_difference(&self, other: &Self) -> Self {
+        self.combine(other, |a, b| a != b)
+    }
+
+    pub fn complement(&self) -> Self {
+        Self::single(&P::default_route()).difference(self)
+    }
+
+    pub fn is_subset(&self, other: &Self) -> bool {
+        self.difference(other).is_empty()
+    }
+
+    pub fn is_disjoint(&self, other: &Self) -> bool {
+        self.intersection(other).is_empty()
+    }
+}
+
+impl PrefixSet<Ipv4Net> {
+    pub fn contains_addr(&self, addr: &Ipv4Addr) -> bool {
+        self.contains(&Ipv4Net::from(*addr))
+    }
+}
+
+impl<P> Default for PrefixSet<P>
+where
+    P: Prefix + Copy,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<P, D> From<&Ptree<P, D>> for PrefixSet<P>
+where
+    P: Prefix + Copy,
+{
+    fn from(tree: &Ptree<P, D>) -> Self {
+        Self::from_cover(cover_all(tree, &Ptree::<P, ()>::new(), &|a, _| a.is_some()))
+    }
+}
+
+impl<P> FromIterator<P> for PrefixSet<P>
+where
+    P: Prefix + Copy,
+{
+    fn from_iter<I: IntoIterator<Item = P>>(iter: I) -> Self {
+        let mut tree = Ptree::new();
+        for prefix in iter {
+            tree.add(&prefix.to_masked(), ());
+        }
+        Self::from(&tree)
+    }
+}
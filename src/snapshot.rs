@@ -0,0 +1,320 @@
+use std::io::Write;
+use std::marker::PhantomData;
+use std::rc::Rc;
+
+use crate::error::Error;
+use crate::ptree::{Node, Prefix, Ptree};
+use crate::wire::{crc32, Decoder};
+
+// A snapshot is a header followed by the nodes of the trie in preorder and
+// the data of the entries, all big endian:
+//
+//   header  magic "PTRS", version u16, max prefix length u8, reserved u8,
+//           node count u32, entry count u32, data length u32, CRC-32 u32
+//   node    address (4 or 16 bytes), prefix length u8, flags u8,
+//           right child index u32, data offset u32, data length u32
+//
+// The left child of a node is the node following it.  The checksum covers
+// the header up to the checksum itself and everything after the header.
+const MAGIC: &[u8; 4] = b"PTRS";
+const VERSION: u16 = 1;
+const HEADER_LEN: usize = 24;
+
+const FLAG_DATA: u8 = 0x01;
+const FLAG_LEFT: u8 = 0x02;
+const FLAG_RIGHT: u8 = 0x04;
+
+fn record_len<P: Prefix>() -> usize {
+    P::MAX_LEN as usize / 8 + 14
+}
+
+fn be_u32(bytes: &[u8]) -> u32 {
+    u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]])
+}
+
+struct Record<P> {
+    prefix: P,
+    flags: u8,
+    right: usize,
+    data: std::ops::Range<usize>,
+}
+
+/// Read-only view of a table written by `Ptree::write_snapshot()`.  The
+/// snapshot is queried in place, so `bytes` may well be a memory map.
+#[derive(Debug, Clone, Copy)]
+pub struct Snapshot<'a, P> {
+    nodes: &'a [u8],
+    data: &'a [u8],
+    count: usize,
+    _prefix: PhantomData<P>,
+}
+
+impl<'a, P> Snapshot<'a, P>
+where
+    P: Prefix + Copy,
+{
+    /// Checks the header, checksum and node layout of a snapshot.
+    pub fn open(bytes: &'a [u8]) -> Result<Self, Error> {
+        let mut decoder = Decoder::new(bytes);
+        if decoder.bytes(4)? != MAGIC {
+            return Err(Error::InvalidData("not a snapshot".to_string()));
+        }
+        let version = decoder.u16()?;
+        if version != VERSION {
+            return Err(Error::UnsupportedVersion(version));
+        }
+        let max_len = decoder.u8()?;
+        if max_len != P::MAX_LEN {
+            return Err(Error::AddressFamilyMismatch(format!(
+                "snapshot of /{} prefixes",
+                max_len
+            )));
+        }
+        decoder.u8()?;
+        let nodes = decoder.u32()? as usize;
+        let count = decoder.u32()? as usize;
+        let data_len = decoder.u32()? as usize;
+        let checksum = decoder.u32()?;
+
+        let nodes_len = nodes
+            .checked_mul(record_len::<P>())
+            .ok_or(Error::Truncated)?;
+        let body = &bytes[HEADER_LEN..];
+        if body.len() < nodes_len.saturating_add(data_len) {
+            return Err(Error::Truncated);
+        }
+        if body.len() > nodes_len + data_len {
+            return Err(Error::InvalidData("trailing bytes".to_string()));
+        }
+        if crc32(crc32(0, &bytes[..HEADER_LEN - 4]), body) != checksum {
+            return Err(Error::Checksum);
+        }
+
+        let snapshot = Snapshot {
+            nodes: &body[..nodes_len],
+            data: &body[nodes_len..],
+            count,
+            _prefix: PhantomData,
+        };
+        snapshot.check(nodes)?;
+        Ok(snapshot)
+    }
+
+    // Makes sure that walking the nodes can neither loop nor index out of
+    // bounds, so that queries need no further checks.
+    fn check(&self, nodes: usize) -> Result<(), Error> {
+        let invalid = |index: usize| Error::InvalidData(format!("snapshot node {}", index));
+        let addr_len = P::MAX_LEN as usize / 8;
+        let mut count = 0;
+        for index in 0..nodes {
+            let bytes = &self.nodes[index * record_len::<P>()..];
+            let len = bytes[addr_len];
+            if len > P::MAX_LEN || P::from_octets(&bytes[..addr_len], len).is_none() {
+                return Err(invalid(index));
+            }
+            let record = self.record(index);
+            if record.flags & FLAG_LEFT != 0 && index + 1 >= nodes {
+                return Err(invalid(index));
+            }
+            if record.flags & FLAG_RIGHT != 0 && (record.right <= index || record.right >= nodes) {
+                return Err(invalid(index));
+            }
+            if record.flags & FLAG_DATA != 0 {
+                if record.data.start > record.data.end || record.data.end > self.data.len() {
+                    return Err(invalid(index));
+                }
+                count += 1;
+            }
+        }
+        if count != self.count {
+            return Err(Error::InvalidData("snapshot entry count".to_string()));
+        }
+        Ok(())
+    }
+
+    fn record(&self, index: usize) -> Record<P> {
+        let addr_len = P::MAX_LEN as usize / 8;
+        let bytes = &self.nodes[index * record_len::<P>()..(index + 1) * record_len::<P>()];
+        let prefix = P::from_octets(&bytes[..addr_len], bytes[addr_len]).unwrap();
+        let offset = be_u32(&bytes[addr_len + 6..]) as usize;
+        Record {
+            prefix,
+            flags: bytes[addr_len + 1],
+            right: be_u32(&bytes[addr_len + 2..]) as usize,
+            data: offset..offset + be_u32(&bytes[addr_len + 10..]) as usize,
+        }
+    }
+
+    fn child(&self, index: usize, record: &Record<P>, bit: u8) -> Option<usize> {
+        match bit {
+            0 if record.flags & FLAG_LEFT != 0 => Some(index + 1),
+            1 if record.flags & FLAG_RIGHT != 0 => Some(record.right),
+            _ => None,
+        }
+    }
+
+    fn nodes(&self) -> usize {
+        self.nodes.len() / record_len::<P>()
+    }
+
+    pub fn len(&self) -> usize {
+        self.count
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+
+    /// Longest match, returning the matching prefix and its data.
+    pub fn lookup(&self, prefix: &P) -> Option<(P, &'a [u8])> {
+        let prefix = &prefix.to_masked();
+        let mut cursor = if self.nodes() > 0 { Some(0) } else { None };
+        let mut matched = None;
+
+        while let Some(index) = cursor {
+            let record = self.record(index);
+            if record.prefix.prefix_len() > prefix.prefix_len() || !record.prefix.contains(prefix) {
+                break;
+            }
+            if record.flags & FLAG_DATA != 0 {
+                matched = Some((record.prefix, &self.data[record.data.clone()]));
+            }
+            if record.prefix.prefix_len() == prefix.prefix_len() {
+                break;
+            }
+            cursor = self.child(index, &record, prefix.bit_at(record.prefix.prefix_len()));
+        }
+        matched
+    }
+
+    pub fn lookup_exact(&self, prefix: &P) -> Option<&'a [u8]> {
+        match self.lookup(prefix) {
+            Some((matched, data)) if matched.prefix_len() == prefix.prefix_len() => Some(data),
+            _ => None,
+        }
+    }
+
+    /// Iterates over the entries in the same order as `Ptree::iter()`.
+    pub fn iter(&self) -> SnapshotIter<'a, P> {
+        SnapshotIter {
+            snapshot: *self,
+            index: 0,
+        }
+    }
+}
+
+pub struct SnapshotIter<'a, P> {
+    snapshot: Snapshot<'a, P>,
+    index: usize,
+}
+
+impl<'a, P> Iterator for SnapshotIter<'a, P>
+where
+    P: Prefix + Copy,
+{
+    type Item = (P, &'a [u8]);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.index < self.snapshot.nodes() {
+            let record = self.snapshot.record(self.index);
+            self.index += 1;
+            if record.flags & FLAG_DATA != 0 {
+                return Some((record.prefix, &self.snapshot.data[record.data]));
+            }
+        }
+        None
+    }
+}
+
+impl<P, D> Ptree<P, D>
+where
+    P: Prefix + Copy,
+{
+    /// Writes the table as a snapshot for `Snapshot::open()`, with the data
+    /// of each entry encoded by `encode`.
+    pub fn write_snapshot<W, F>(&self, mut writer: W, encode: F) -> Result<(), Error>
+    where
+        W: Write,
+        F: Fn(&D) -> Vec<u8>,
+    {
+        let mut nodes = Vec::new();
+        let mut data = Vec::new();
+        let mut entries = 0u32;
+        if let Some(top) = self.top.clone() {
+            write_node(&top, &mut nodes, &mut data, &mut entries, &encode)?;
+        }
+        let too_large = || Error::InvalidData("snapshot too large".to_string());
+        let node_count = u32::try_from(nodes.len() / record_len::<P>()).map_err(|_| too_large())?;
+        let data_len = u32::try_from(data.len()).map_err(|_| too_large())?;
+
+        let mut header = Vec::with_capacity(HEADER_LEN);
+        header.extend_from_slice(MAGIC);
+        header.extend_from_slice(&VERSION.to_be_bytes());
+        header.push(P::MAX_LEN);
+        header.push(0);
+        header.extend_from_slice(&node_count.to_be_bytes());
+        header.extend_from_slice(&entries.to_be_bytes());
+        header.extend_from_slice(&data_len.to_be_bytes());
+        let checksum = crc32(crc32(crc32(0, &header), &nodes), &data);
+        header.extend_from_slice(&checksum.to_be_bytes());
+
+        writer.write_all(&header)?;
+        writer.write_all(&nodes)?;
+        writer.write_all(&data)?;
+        Ok(())
+    }
+}
+
+fn write_node<P, D, F>(
+    node: &Rc<Node<P, D>>,
+    nodes: &mut Vec<u8>,
+    data: &mut Vec<u8>,
+    entries: &mut u32,
+    encode: &F,
+) -> Result<(), Error>
+where
+    P: Prefix + Copy,
+    F: Fn(&D) -> Vec<u8>,
+{
+    let left = node.child_with(0);
+    let right = node.child_with(1);
+    let mut flags = 0;
+    let (offset, len) = match node.data.borrow().as_ref() {
+        Some(value) => {
+            flags |= FLAG_DATA;
+            *entries += 1;
+            let offset = data.len();
+            data.extend_from_slice(&encode(value));
+            (offset, data.len() - offset)
+        }
+        None => (0, 0),
+    };
+    let offset =
+        u32::try_from(offset).map_err(|_| Error::InvalidData("snapshot too large".to_string()))?;
+
+    if left.is_some() {
+        flags |= FLAG_LEFT;
+    }
+    if right.is_some() {
+        flags |= FLAG_RIGHT;
+    }
+
+    let start = nodes.len();
+    nodes.extend_from_slice(&node.prefix.octets());
+    nodes.push(node.prefix.prefix_len());
+    nodes.push(flags);
+    nodes.extend_from_slice(&0u32.to_be_bytes());
+    nodes.extend_from_slice(&offset.to_be_bytes());
+    nodes.extend_from_slice(&(len as u32).to_be_bytes());
+
+    if let Some(left) = left {
+        write_node(&left, nodes, data, entries, encode)?;
+    }
+    if let Some(right) = right {
+        let index = (nodes.len() / record_len::<P>()) as u32;
+        let at = start + P::MAX_LEN as usize / 8 + 2;
+        nodes[at..at + 4].copy_from_slice(&index.to_be_bytes());
+        write_node(&right, nodes, data, entries, encode)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,137 @@
+use std::collections::BTreeMap;
+
+use crate::ptree::{Node, Prefix, Ptree};
+
+#[derive(Debug)]
+pub struct Stats<D> {
+    pub entries: usize,
+    pub lengths: BTreeMap<u8, usize>,
+    /// Addresses covered by the entries.  The 2^128 addresses of IPv6 `::/0`
+    /// do not fit, so with it in the table this and the `covered_by_data`
+    /// count of its data are one short, as `Prefix::address_count()` is.
+    pub covered: u128,
+    pub covered_by_data: BTreeMap<D, u128>,
+}
+
+impl<P, D> Ptree<P, D>
+where
+    P: Prefix + Copy,
+    D: Clone + Ord,
+{
+    /// Counts entries per prefix length and the addresses each data value
+    /// is used for, with more-specific entries overriding covering ones.
+    pub fn stats(&self) -> Stats<D> {
+        let mut stats = Stats {
+            entries: 0,
+            lengths: BTreeMap::new(),
+            covered: 0,
+            covered_by_data: BTreeMap::new(),
+        };
+
+        // Data nodes in tree order with the addresses they resolve.  `stack`
+        // holds the indexes of the entries covering the current one.
+        let mut entries: Vec<(P, u128, D)> = Vec::new();
+        let mut stack: Vec<usize> = Vec::new();
+
+        for node in self.iter() {
+            let Some(data) = node.data.borrow().clone() else {
+                continue;
+            };
+            while let Some(&index) = stack.last() {
+                if entries[index].0.contains(&node.prefix) {
+                    break;
+                }
+                stack.pop();
+            }
+            let count = node.prefix.address_count();
+            if let Some(&index) = stack.last() {
+                entries[index].1 -= count;
+            }
+            stack.push(entries.len());
+            entries.push((node.prefix, count, data));
+        }
+
+        for (prefix, count, data) in entries.into_iter() {
+            stats.entries += 1;
+            *stats.lengths.entry(prefix.prefix_len()).or_insert(0) += 1;
+            stats.covered = stats.covered.saturating_add(count);
+            let covered = stats.covered_by_data.entry(data).or_insert(0);
+            *covered = covered.saturating_add(count);
+        }
+        stats
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Structure {
+    pub nodes: usize,
+    pub glue_nodes: usize,
+    pub data_nodes: usize,
+    pub max_depth: usize,
+    pub average_depth: f64,
+    pub depths: BTreeMap<usize, usize>,
+    pub node_bytes: usize,
+    pub data_bytes: usize,
+}
+
+impl<P, D> Ptree<P, D>
+where
+    P: Prefix + Copy,
+{
+    /// Reports the shape of the trie.  Depths are counted for data nodes
+    /// only, with the top node at depth 0.
+    pub fn structure(&self) -> Structure {
+        self.structure_with(|_| 0)
+    }
+
+    /// Same as `structure()`, with `data_heap` giving the heap bytes owned
+    /// by each data value.  `data_bytes` is the inline size of the values,
+    /// which is also part of `node_bytes`, plus their heap bytes.
+    pub fn structure_with<F>(&self, data_heap: F) -> Structure
+    where
+        F: Fn(&D) -> usize,
+    {
+        let mut structure = Structure {
+            nodes: 0,
+            glue_nodes: 0,
+            data_nodes: 0,
+            max_depth: 0,
+            average_depth: 0.0,
+            depths: BTreeMap::new(),
+            node_bytes: 0,
+            data_bytes: 0,
+        };
+
+        let mut total_depth = 0;
+        let mut stack = Vec::new();
+        if let Some(top) = self.top.clone() {
+            stack.push((top, 0));
+        }
+
+        while let Some((node, depth)) = stack.pop() {
+            structure.nodes += 1;
+            if let Some(data) = node.data.borrow().as_ref() {
+                structure.data_nodes += 1;
+                structure.data_bytes += std::mem::size_of::<D>() + data_heap(data);
+                structure.max_depth = structure.max_depth.max(depth);
+                *structure.depths.entry(depth).or_insert(0) += 1;
+                total_depth += depth;
+            } else {
+                structure.glue_nodes += 1;
+            }
+            for bit in 0..2 {
+                if let Some(child) = node.child_with(bit) {
+                    stack.push((child, depth + 1));
+                }
+            }
+        }
+
+        // Each node is its own Rc allocation with strong and weak counters.
+        structure.node_bytes = structure.nodes
+            * (std::mem::size_of::<Node<P, D>>() + 2 * std::mem::size_of::<usize>());
+        if structure.data_nodes > 0 {
+            structure.average_depth = total_depth as f64 / structure.data_nodes as f64;
+        }
+        structure
+    }
+}
@@ -0,0 +1,112 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc::{self, Receiver, SyncSender, TrySendError};
+use std::sync::Arc;
+
+use crate::observer::{Observer, ObserverId};
+use crate::ptree::{Prefix, Ptree};
+
+/// A change to an entry as delivered to subscriptions.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Change<P, D> {
+    Added(P, D),
+    Replaced(P, D, D),
+    Removed(P, D),
+}
+
+/// The receiving end of `Ptree::subscribe()`.
+#[derive(Debug)]
+pub struct Subscription<P, D> {
+    id: ObserverId,
+    receiver: Receiver<Change<P, D>>,
+    dropped: Arc<AtomicUsize>,
+}
+
+impl<P, D> Subscription<P, D> {
+    pub fn id(&self) -> ObserverId {
+        self.id
+    }
+
+    pub fn receiver(&self) -> &Receiver<Change<P, D>> {
+        &self.receiver
+    }
+
+    /// Number of changes lost so far because the buffer was full.
+    pub fn dropped(&self) -> usize {
+        self.dropped.load(Ordering::Relaxed)
+    }
+}
+
+struct Subscriber<P, D> {
+    prefix: P,
+    sender: SyncSender<Change<P, D>>,
+    dropped: Arc<AtomicUsize>,
+}
+
+impl<P, D> Subscriber<P, D>
+where
+    P: Prefix,
+{
+    fn send(&self, prefix: &P, change: impl FnOnce() -> Change<P, D>) {
+        if !self.prefix.contains(prefix) {
+            return;
+        }
+        if let Err(TrySendError::Full(_)) = self.sender.try_send(change()) {
+            self.dropped.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+}
+
+impl<P, D> Observer<P, D> for Subscriber<P, D>
+where
+    P: Prefix + Copy,
+    D: Clone,
+{
+    fn added(&mut self, prefix: &P, data: &D) {
+        self.send(prefix, || Change::Added(*prefix, data.clone()));
+    }
+
+    fn replaced(&mut self, prefix: &P, old: &D, new: &D) {
+        self.send(prefix, || {
+            Change::Replaced(*prefix, old.clone(), new.clone())
+        });
+    }
+
+    fn removed(&mut self, prefix: &P, data: &D) {
+        self.send(prefix, || Change::Removed(*prefix, data.clone()));
+    }
+
+    // The subscription holds the only other reference to the counter.
+    fn is_closed(&self) -> bool {
+        Arc::strong_count(&self.dropped) == 1
+    }
+}
+
+impl<P, D> Ptree<P, D>
+where
+    P: Prefix + Copy + 'static,
+    D: Clone + 'static,
+{
+    /// Subscribes to the changes of the entries within `prefix`.  Up to
+    /// `capacity` changes are buffered, and changes made while the buffer
+    /// is full are counted by `Subscription::dropped()`.  Dropping the
+    /// subscription ends it at the next change.
+    pub fn subscribe(&mut self, prefix: &P, capacity: usize) -> Subscription<P, D> {
+        let (sender, receiver) = mpsc::sync_channel(capacity);
+        let dropped = Arc::new(AtomicUsize::new(0));
+        let id = self.add_observer(Box::new(Subscriber {
+            prefix: prefix.to_masked(),
+            sender,
+            dropped: dropped.clone(),
+        }));
+        Subscription {
+            id,
+            receiver,
+            dropped,
+        }
+    }
+
+    /// Ends a subscription, returning false if it had already ended.
+    pub fn unsubscribe(&mut self, subscription: &Subscription<P, D>) -> bool {
+        self.remove_observer(subscription.id()).is_some()
+    }
+}
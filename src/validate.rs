@@ -0,0 +1,124 @@
+use std::fmt;
+use std::rc::Rc;
+
+use crate::ptree::{Prefix, Ptree};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Violation<P> {
+    TopHasParent(P),
+    NotMasked(P),
+    NotContained { parent: P, child: P },
+    WrongBranch { parent: P, child: P, bit: u8 },
+    WrongParent { parent: P, child: P },
+    Glue { prefix: P, children: usize },
+}
+
+impl<P: fmt::Display> fmt::Display for Violation<P> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Violation::TopHasParent(prefix) => write!(f, "top node {} has a parent", prefix),
+            Violation::NotMasked(prefix) => write!(f, "{} has host bits set", prefix),
+            Violation::NotContained { parent, child } => {
+                write!(f, "{} is not contained in parent {}", child, parent)
+            }
+            Violation::WrongBranch { parent, child, bit } => {
+                write!(f, "{} is placed at bit {} of {}", child, bit, parent)
+            }
+            Violation::WrongParent { parent, child } => {
+                write!(
+                    f,
+                    "parent pointer of {} does not point to {}",
+                    child, parent
+                )
+            }
+            Violation::Glue { prefix, children } => {
+                write!(f, "glue node {} has {} children", prefix, children)
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ValidationError<P> {
+    pub violations: Vec<Violation<P>>,
+}
+
+impl<P: fmt::Display> fmt::Display for ValidationError<P> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} trie invariant violations", self.violations.len())?;
+        for violation in self.violations.iter() {
+            write!(f, "\n  {}", violation)?;
+        }
+        Ok(())
+    }
+}
+
+impl<P: fmt::Debug + fmt::Display> std::error::Error for ValidationError<P> {}
+
+impl<P, D> Ptree<P, D>
+where
+    P: Prefix + Copy + PartialEq,
+{
+    /// Checks every structural invariant of the trie and reports all the
+    /// violations found.  There is no entry counter to check against, as
+    /// `len()` counts the entries by walking the tree.
+    pub fn validate(&self) -> Result<(), ValidationError<P>> {
+        let mut violations = Vec::new();
+        let mut stack = Vec::new();
+
+        if let Some(top) = self.top.clone() {
+            if top.parent().is_some() {
+                violations.push(Violation::TopHasParent(top.prefix));
+            }
+            stack.push(top);
+        }
+
+        while let Some(node) = stack.pop() {
+            if node.prefix.to_masked() != node.prefix {
+                violations.push(Violation::NotMasked(node.prefix));
+            }
+
+            let mut children = 0;
+            for bit in 0..2 {
+                let Some(child) = node.child_with(bit) else {
+                    continue;
+                };
+                children += 1;
+
+                let (parent, prefix) = (node.prefix, child.prefix);
+                if prefix.prefix_len() <= parent.prefix_len() || !parent.contains(&prefix) {
+                    violations.push(Violation::NotContained {
+                        parent,
+                        child: prefix,
+                    });
+                } else if prefix.bit_at(parent.prefix_len()) != bit {
+                    violations.push(Violation::WrongBranch {
+                        parent,
+                        child: prefix,
+                        bit,
+                    });
+                }
+                if !child.parent().is_some_and(|p| Rc::ptr_eq(&p, &node)) {
+                    violations.push(Violation::WrongParent {
+                        parent,
+                        child: prefix,
+                    });
+                }
+                stack.push(child);
+            }
+
+            if !node.has_data() && children < 2 {
+                violations.push(Violation::Glue {
+                    prefix: node.prefix,
+                    children,
+                });
+            }
+        }
+
+        if violations.is_empty() {
+            Ok(())
+        } else {
+            Err(ValidationError { violations })
+        }
+    }
+}
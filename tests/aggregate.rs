@@ -0,0 +1,90 @@
+use ipnet::Ipv4Net;
+use ptree::*;
+
+fn route_ipv4_add(ptree: &mut Ptree<Ipv4Net, i32>, str: &str, data: i32) {
+    let prefix: Ipv4Net = str.parse().unwrap();
+    ptree.add(&prefix, data);
+}
+
+fn routes(ptree: &Ptree<Ipv4Net, i32>) -> Vec<(String, i32)> {
+    ptree
+        .iter()
+        .map(|n| (n.prefix.to_string(), n.data.borrow().unwrap()))
+        .collect()
+}
+
+fn same(a: &i32, b: &i32) -> Option<i32> {
+    if a == b {
+        Some(*a)
+    } else {
+        None
+    }
+}
+
+#[test]
+fn aggregate_siblings() {
+    let mut top = Ptree::<Ipv4Net, i32>::new();
+    route_ipv4_add(&mut top, "10.0.0.0/25", 1);
+    route_ipv4_add(&mut top, "10.0.0.128/25", 1);
+    route_ipv4_add(&mut top, "10.0.1.0/25", 1);
+    route_ipv4_add(&mut top, "10.0.1.128/25", 2);
+
+    let agg = top.aggregate(0, same);
+    assert_eq!(
+        routes(&agg),
+        [
+            ("10.0.0.0/24".to_string(), 1),
+            ("10.0.1.0/25".to_string(), 1),
+            ("10.0.1.128/25".to_string(), 2)
+        ]
+    );
+    assert!(agg.forwarding_diff(&top).is_empty());
+}
+
+#[test]
+fn aggregate_repeated() {
+    let mut top = Ptree::<Ipv4Net, i32>::new();
+    route_ipv4_add(&mut top, "10.0.0.0/26", 1);
+    route_ipv4_add(&mut top, "10.0.0.64/26", 1);
+    route_ipv4_add(&mut top, "10.0.0.128/26", 1);
+    route_ipv4_add(&mut top, "10.0.0.192/26", 1);
+    route_ipv4_add(&mut top, "10.0.1.0/24", 1);
+
+    assert_eq!(
+        routes(&top.aggregate(0, same)),
+        [("10.0.0.0/23".to_string(), 1)]
+    );
+    assert_eq!(
+        routes(&top.aggregate(24, same)),
+        [
+            ("10.0.0.0/24".to_string(), 1),
+            ("10.0.1.0/24".to_string(), 1)
+        ]
+    );
+}
+
+#[test]
+fn aggregate_existing_covering() {
+    let mut top = Ptree::<Ipv4Net, i32>::new();
+    route_ipv4_add(&mut top, "10.0.0.0/24", 2);
+    route_ipv4_add(&mut top, "10.0.0.0/25", 1);
+    route_ipv4_add(&mut top, "10.0.0.128/25", 1);
+
+    assert_eq!(routes(&top.aggregate(0, same)), routes(&top));
+
+    let agg = top.aggregate(0, |a, b| Some(*a.max(b)));
+    assert_eq!(routes(&agg), [("10.0.0.0/24".to_string(), 2)]);
+}
+
+#[test]
+fn aggregate_validate() {
+    let mut top = Ptree::<Ipv4Net, i32>::new();
+    route_ipv4_add(&mut top, "10.0.0.0/26", 1);
+    route_ipv4_add(&mut top, "10.0.0.64/26", 1);
+    route_ipv4_add(&mut top, "10.0.0.128/25", 1);
+    route_ipv4_add(&mut top, "10.0.0.192/27", 2);
+
+    let agg = top.aggregate(0, same);
+    assert_eq!(agg.len(), 2);
+    assert!(agg.validate().is_ok());
+}
@@ -0,0 +1,230 @@
+use ipnet::{Ipv4Net, Ipv6Net};
+use ptree::*;
+
+fn lookup(top: &Ptree<impl Prefix + Copy + std::str::FromStr, Route>, addr: &str) -> Route {
+    let n = top.lookup_str(addr).unwrap().unwrap();
+    let route = n.data.borrow().clone().unwrap();
+    route
+}
+
+fn nexthop(addr: Option<&str>, interface: Option<&str>) -> Nexthop {
+    Nexthop {
+        addr: addr.map(|a| a.parse().unwrap()),
+        interface: interface.map(str::to_string),
+    }
+}
+
+const IP_ROUTE: &str = "\
+default via 192.168.1.1 dev eth0 proto dhcp src 192.168.1.10 metric 100
+10.0.0.0/8 via 10.1.1.1 dev eth1 proto static
+10.2.0.0/16 proto bgp metric 20
+\tnexthop via 10.1.1.1 dev eth1 weight 1
+\tnexthop via 10.1.1.2 dev eth2 weight 1
+blackhole 10.9.0.0/16 proto static
+192.168.1.0/24 dev eth0 proto kernel scope link src 192.168.1.10 metric 100
+";
+
+#[test]
+fn cli_ip_route() {
+    let mut top = Ptree::<Ipv4Net, Route>::new();
+    assert_eq!(top.read_ip_route(IP_ROUTE).unwrap(), 5);
+
+    let route = lookup(&top, "8.8.8.8");
+    assert_eq!(route.protocol, "dhcp");
+    assert_eq!(route.metric, Some(100));
+    assert_eq!(route.nexthops, [nexthop(Some("192.168.1.1"), Some("eth0"))]);
+
+    let route = lookup(&top, "10.2.3.4");
+    assert_eq!(route.protocol, "bgp");
+    assert_eq!(
+        route.nexthops,
+        [
+            nexthop(Some("10.1.1.1"), Some("eth1")),
+            nexthop(Some("10.1.1.2"), Some("eth2"))
+        ]
+    );
+
+    assert!(lookup(&top, "10.9.0.1").nexthops.is_empty());
+    let route = lookup(&top, "192.168.1.20");
+    assert_eq!(route.protocol, "kernel");
+    assert_eq!(route.nexthops, [nexthop(None, Some("eth0"))]);
+
+    let err = top
+        .read_ip_route("10.0.0.0/8 via 10.0.0.300\n")
+        .unwrap_err();
+    assert!(matches!(err, Error::Line(1, _)));
+}
+
+#[test]
+fn cli_ip6_route() {
+    let mut top = Ptree::<Ipv6Net, Route>::new_ipv6();
+    let text = "\
+2001:db8::/32 via fe80::1 dev eth0 proto static metric 1024 pref medium
+fe80::/64 dev eth0 proto kernel metric 256 pref medium
+default via fe80::1 dev eth0 proto ra metric 1024 expires 1798sec pref medium
+";
+    assert_eq!(top.read_ip_route(text).unwrap(), 3);
+    assert_eq!(lookup(&top, "2001:db8::1").protocol, "static");
+    assert_eq!(lookup(&top, "2001:db9::1").protocol, "ra");
+}
+
+#[cfg(feature = "json")]
+const FRR_JSON: &str = r#"{
+  "0.0.0.0/0": [
+    {"prefix": "0.0.0.0/0", "protocol": "static", "selected": true, "distance": 1,
+     "metric": 0, "nexthops": [{"ip": "192.0.2.1", "afi": "ipv4", "interfaceName": "eth0", "active": true}]}
+  ],
+  "10.0.0.0/8": [
+    {"prefix": "10.0.0.0/8", "protocol": "ospf", "distance": 110, "metric": 20,
+     "nexthops": [{"ip": "10.1.1.1", "interfaceName": "eth1"}]},
+    {"prefix": "10.0.0.0/8", "protocol": "bgp", "selected": true, "distance": 20, "metric": 0,
+     "nexthops": [{"ip": "10.1.1.2", "interfaceName": "eth2"},
+                  {"ip": "10.1.1.3", "interfaceName": "eth3"}]}
+  ],
+  "192.0.2.0/24": [
+    {"prefix": "192.0.2.0/24", "protocol": "connected", "selected": true, "distance": 0,
+     "metric": 0, "nexthops": [{"directlyConnected": true, "interfaceName": "eth0"}]}
+  ]
+}"#;
+
+#[cfg(feature = "json")]
+#[test]
+fn cli_frr_json() {
+    let mut top = Ptree::<Ipv4Net, Route>::new();
+    assert_eq!(top.read_frr_json(FRR_JSON).unwrap(), 3);
+
+    let route = lookup(&top, "10.1.2.3");
+    assert_eq!(route.protocol, "bgp");
+    assert_eq!(route.distance, Some(20));
+    assert_eq!(route.nexthops.len(), 2);
+
+    let route = lookup(&top, "192.0.2.9");
+    assert_eq!(route.nexthops, [nexthop(None, Some("eth0"))]);
+    assert_eq!(lookup(&top, "8.8.8.8").protocol, "static");
+
+    assert!(matches!(
+        top.read_frr_json("{\"10.0.0.0/8\": [}"),
+        Err(Error::Json(_))
+    ));
+}
+
+const CISCO: &str = "\
+Codes: L - local, C - connected, S - static, R - RIP, M - mobile, B - BGP
+       D - EIGRP, EX - EIGRP external, O - OSPF, IA - OSPF inter area
+       * - candidate default, U - per-user static route, o - ODR
+
+Gateway of last resort is 10.0.0.1 to network 0.0.0.0
+
+S*    0.0.0.0/0 [1/0] via 10.0.0.1
+      10.0.0.0/8 is variably subnetted, 3 subnets, 3 masks
+C        10.0.0.0/24 is directly connected, GigabitEthernet0/0
+L        10.0.0.2/32 is directly connected, GigabitEthernet0/0
+O        10.1.0.0/16 [110/20] via 10.0.0.1, 00:01:02, GigabitEthernet0/0
+                     [110/20] via 10.0.0.3, 00:01:02, GigabitEthernet0/1
+      172.16.0.0/24 is subnetted, 2 subnets
+O IA     172.16.1.0 [110/30] via 10.0.0.1, 00:00:10, GigabitEthernet0/0
+B        172.16.2.0 [20/0] via 192.0.2.1, 1d02h
+";
+
+#[test]
+fn cli_cisco() {
+    let mut top = Ptree::<Ipv4Net, Route>::new();
+    assert_eq!(top.read_cisco(CISCO).unwrap(), 6);
+
+    let route = lookup(&top, "8.8.8.8");
+    assert_eq!(route.protocol, "static");
+    assert_eq!(route.distance, Some(1));
+    assert_eq!(route.nexthops, [nexthop(Some("10.0.0.1"), None)]);
+
+    let route = lookup(&top, "10.1.2.3");
+    assert_eq!(route.protocol, "ospf");
+    assert_eq!(route.metric, Some(20));
+    assert_eq!(
+        route.nexthops,
+        [
+            nexthop(Some("10.0.0.1"), Some("GigabitEthernet0/0")),
+            nexthop(Some("10.0.0.3"), Some("GigabitEthernet0/1"))
+        ]
+    );
+
+    let n = top.lookup_str("172.16.1.1").unwrap().unwrap();
+    assert_eq!(n.prefix.to_string(), "172.16.1.0/24");
+    assert_eq!(lookup(&top, "172.16.2.1").protocol, "bgp");
+    assert_eq!(lookup(&top, "172.16.2.1").nexthops[0].interface, None);
+    assert_eq!(lookup(&top, "10.0.0.2").protocol, "local");
+    assert_eq!(lookup(&top, "10.0.0.9").protocol, "connected");
+}
+
+#[test]
+fn cli_cisco_summary() {
+    let mut top = Ptree::<Ipv4Net, Route>::new();
+    let text = "\
+D        10.0.0.0/8 is a summary, 00:05:12, Null0
+O        172.16.0.0/16 is a summary, 1d02h, Null0
+D        10.1.0.0/16 [90/156160] via 10.0.0.1, 00:05:12, GigabitEthernet0/0
+";
+    assert_eq!(top.read_cisco(text).unwrap(), 3);
+
+    let route = lookup(&top, "10.2.0.1");
+    assert_eq!(route.protocol, "eigrp");
+    assert_eq!(route.nexthops, [nexthop(None, Some("Null0"))]);
+    assert_eq!(lookup(&top, "172.16.1.1").protocol, "ospf");
+    assert_eq!(lookup(&top, "10.1.0.1").metric, Some(156160));
+}
+
+#[test]
+fn cli_cisco_subnetted() {
+    let mut top = Ptree::<Ipv4Net, Route>::new();
+    let text = "      10.0.0.0/24 is subnetted, 2 subnets
+C        10.1.1.0 is directly connected, GigabitEthernet0/1
+C        10.1.2.0 is directly connected, GigabitEthernet0/2
+      172.16.0.0/16 is variably subnetted, 2 subnets, 2 masks
+C        172.16.0.0/24 is directly connected, GigabitEthernet0/3
+";
+    assert_eq!(top.read_cisco(text).unwrap(), 3);
+    let n = top.lookup_str("10.1.2.9").unwrap().unwrap();
+    assert_eq!(n.prefix.to_string(), "10.1.2.0/24");
+    let n = top.lookup_str("172.16.0.9").unwrap().unwrap();
+    assert_eq!(n.prefix.to_string(), "172.16.0.0/24");
+
+    // The length of an `is subnetted` header only applies within its
+    // classful network.
+    let text = "      10.0.0.0/24 is subnetted, 1 subnets
+C        10.1.1.0 is directly connected, GigabitEthernet0/1
+C        172.16.0.0 is directly connected, GigabitEthernet0/2
+";
+    let err = Ptree::<Ipv4Net, Route>::new().read_cisco(text).unwrap_err();
+    assert!(matches!(&err, Error::Line(3, e) if matches!(**e, Error::Parse(_))));
+
+    // Nor does it outlive a line giving its own length.
+    let text = "      10.0.0.0/24 is subnetted, 1 subnets
+C        10.1.1.0 is directly connected, GigabitEthernet0/1
+S        10.2.0.0/16 [1/0] via 10.1.1.2
+C        10.3.3.0 is directly connected, GigabitEthernet0/2
+";
+    let mut top = Ptree::<Ipv4Net, Route>::new();
+    assert_eq!(top.read_cisco(text).unwrap(), 3);
+    let n = top.lookup_str("10.3.3.0").unwrap().unwrap();
+    assert_eq!(n.prefix.to_string(), "10.3.3.0/32");
+}
+
+#[test]
+fn cli_cisco_wrapped_nexthop() {
+    let mut top = Ptree::<Ipv4Net, Route>::new();
+    let text = "\
+B        192.168.100.0/24
+           [20/0] via 10.0.0.9, 1w2d
+B        192.168.101.0/24 [20/0] via 10.0.0.10, 1w2d
+";
+    assert_eq!(top.read_cisco(text).unwrap(), 2);
+
+    let route = lookup(&top, "192.168.100.1");
+    assert_eq!(route.protocol, "bgp");
+    assert_eq!(route.distance, Some(20));
+    assert_eq!(route.metric, Some(0));
+    assert_eq!(route.nexthops, [nexthop(Some("10.0.0.9"), None)]);
+    assert_eq!(
+        lookup(&top, "192.168.101.1").nexthops,
+        [nexthop(Some("10.0.0.10"), None)]
+    );
+}
@@ -0,0 +1,94 @@
+use ipnet::Ipv4Net;
+use ptree::*;
+use std::net::Ipv4Addr;
+
+fn route_ipv4_add(ptree: &mut Ptree<Ipv4Net, i32>, str: &str, data: i32) {
+    let prefix: Ipv4Net = str.parse().unwrap();
+    ptree.add(&prefix, data);
+}
+
+fn routes(ptree: &Ptree<Ipv4Net, i32>) -> Vec<(String, i32)> {
+    ptree
+        .iter()
+        .map(|n| (n.prefix.to_string(), n.data.borrow().unwrap()))
+        .collect()
+}
+
+#[test]
+fn compress_redundant() {
+    let mut top = Ptree::<Ipv4Net, i32>::new();
+    route_ipv4_add(&mut top, "10.0.0.0/8", 1);
+    route_ipv4_add(&mut top, "10.1.0.0/16", 1);
+    route_ipv4_add(&mut top, "10.2.0.0/16", 2);
+    route_ipv4_add(&mut top, "10.2.1.0/24", 1);
+
+    let fib = top.compress();
+    assert_eq!(
+        routes(&fib),
+        [
+            ("10.0.0.0/8".to_string(), 1),
+            ("10.2.0.0/16".to_string(), 2),
+            ("10.2.1.0/24".to_string(), 1)
+        ]
+    );
+    assert!(fib.forwarding_equivalent(&top));
+}
+
+#[test]
+fn compress_majority() {
+    // Three of four quarters share data 1, so ORTC covers the block with 1
+    // and keeps a single exception.
+    let mut top = Ptree::<Ipv4Net, i32>::new();
+    route_ipv4_add(&mut top, "10.0.0.0/26", 1);
+    route_ipv4_add(&mut top, "10.0.0.64/26", 1);
+    route_ipv4_add(&mut top, "10.0.0.128/26", 2);
+    route_ipv4_add(&mut top, "10.0.0.192/26", 1);
+
+    let fib = top.compress();
+    assert_eq!(
+        routes(&fib),
+        [
+            ("10.0.0.0/24".to_string(), 1),
+            ("10.0.0.128/26".to_string(), 2)
+        ]
+    );
+    assert!(fib.forwarding_equivalent(&top));
+}
+
+#[test]
+fn compress_keeps_holes() {
+    let mut top = Ptree::<Ipv4Net, i32>::new();
+    route_ipv4_add(&mut top, "10.0.0.0/25", 1);
+    route_ipv4_add(&mut top, "10.0.1.0/24", 1);
+
+    let fib = top.compress();
+    assert_eq!(routes(&fib), routes(&top));
+    assert!(fib.forwarding_equivalent(&top));
+    assert!(Ptree::<Ipv4Net, i32>::new()
+        .compress()
+        .iter()
+        .next()
+        .is_none());
+}
+
+#[test]
+fn compress_random() {
+    let mut seed: u32 = 1;
+    let mut next = || {
+        seed = seed.wrapping_mul(1103515245).wrapping_add(12345);
+        seed
+    };
+
+    let mut top = Ptree::<Ipv4Net, i32>::new();
+    route_ipv4_add(&mut top, "0.0.0.0/0", 0);
+    for _ in 0..2000 {
+        let addr = Ipv4Addr::from(next() & 0x0fff_ff00 | 0x0a00_0000);
+        let len = 12 + (next() >> 8) % 13;
+        let prefix = Ipv4Net::new(addr, len as u8).unwrap().trunc();
+        top.add(&prefix, ((next() >> 8) % 4) as i32);
+    }
+
+    let fib = top.compress();
+    assert!(fib.forwarding_equivalent(&top));
+    assert!(fib.iter().count() < top.iter().count());
+}
@@ -1,14 +1,9 @@
-use ipnet::Ipv4Net;
+use ipnet::{Ipv4Net, Ipv6Net};
 use ptree::*;
 use std::fs::File;
 use std::io::{BufRead, BufReader};
 use std::time;
 
-fn route_ipv4_add(ptree: &mut Ptree<Ipv4Net, i32>, str: &str, data: i32) {
-    let prefix: Ipv4Net = str.parse().unwrap();
-    ptree.add(&prefix, data);
-}
-
 fn route_ipv4_delete(ptree: &mut Ptree<Ipv4Net, i32>, str: &str) {
     let prefix: Ipv4Net = str.parse().unwrap();
     ptree.delete(&prefix);
@@ -21,12 +16,7 @@ fn ipv4_route_random1() {
     let mut top = Ptree::new();
 
     let file = File::open("tests/data/v4routes-random1.txt").unwrap();
-    let bufferd = BufReader::new(file);
-
-    for line in bufferd.lines() {
-        let line = line.unwrap();
-        route_ipv4_add(&mut top, &line, 0);
-    }
+    top.read_prefixes(BufReader::new(file), 0).unwrap();
     assert_eq!(top.iter().count(), 569770);
 
     let file = File::open("tests/data/v4routes-random1.txt").unwrap();
@@ -41,3 +31,26 @@ fn ipv4_route_random1() {
 
     println!("Elapsed {:?}", now.elapsed());
 }
+
+fn load_ipv6(path: &str) -> Ptree<Ipv6Net, i32> {
+    let mut top = Ptree::new_ipv6();
+
+    let file = File::open(path).unwrap();
+    top.read_prefixes(BufReader::new(file), 0).unwrap();
+    top
+}
+
+#[test]
+fn ipv6_route_structure() {
+    let top1 = load_ipv6("tests/data/v6routes-random1.txt");
+    let top2 = load_ipv6("tests/data/v6routes-random2.txt");
+    assert_eq!(top1.iter().count(), 24470);
+
+    let structure = top1.structure();
+    println!("{:?}", structure);
+    assert_eq!(structure.data_nodes, 24470);
+    assert_eq!(structure.nodes, structure.data_nodes + structure.glue_nodes);
+    assert!(structure.glue_nodes < structure.data_nodes);
+    assert_eq!(structure.depths.values().sum::<usize>(), 24470);
+    assert_eq!(structure, top2.structure());
+}
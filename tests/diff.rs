@@ -0,0 +1,76 @@
+use ipnet::Ipv4Net;
+use ptree::*;
+
+fn route_ipv4_add(ptree: &mut Ptree<Ipv4Net, i32>, str: &str, data: i32) {
+    let prefix: Ipv4Net = str.parse().unwrap();
+    ptree.add(&prefix, data);
+}
+
+fn prefixes(list: &[&str]) -> Vec<Ipv4Net> {
+    list.iter().map(|s| s.parse().unwrap()).collect()
+}
+
+#[test]
+fn diff_split_same_nexthop() {
+    let mut old = Ptree::<Ipv4Net, i32>::new();
+    route_ipv4_add(&mut old, "10.0.0.0/16", 1);
+    route_ipv4_add(&mut old, "10.0.1.0/24", 2);
+
+    let mut new = Ptree::<Ipv4Net, i32>::new();
+    route_ipv4_add(&mut new, "10.0.0.0/17", 1);
+    route_ipv4_add(&mut new, "10.0.128.0/17", 1);
+    route_ipv4_add(&mut new, "10.0.1.0/24", 2);
+
+    assert!(old.forwarding_diff(&new).is_empty());
+    assert!(new.forwarding_diff(&old).is_empty());
+}
+
+#[test]
+fn diff_changed_data() {
+    let mut old = Ptree::<Ipv4Net, i32>::new();
+    route_ipv4_add(&mut old, "10.0.0.0/16", 1);
+
+    let mut new = Ptree::<Ipv4Net, i32>::new();
+    route_ipv4_add(&mut new, "10.0.0.0/17", 1);
+    route_ipv4_add(&mut new, "10.0.128.0/17", 2);
+    route_ipv4_add(&mut new, "10.0.1.0/24", 3);
+
+    assert_eq!(
+        old.forwarding_diff(&new),
+        prefixes(&["10.0.1.0/24", "10.0.128.0/17"])
+    );
+}
+
+#[test]
+fn diff_added_and_removed() {
+    let mut old = Ptree::<Ipv4Net, i32>::new();
+    route_ipv4_add(&mut old, "10.0.0.0/8", 1);
+    route_ipv4_add(&mut old, "192.168.0.0/24", 1);
+
+    let mut new = Ptree::<Ipv4Net, i32>::new();
+    route_ipv4_add(&mut new, "10.0.0.0/8", 1);
+    route_ipv4_add(&mut new, "172.16.0.0/12", 1);
+
+    assert_eq!(
+        old.forwarding_diff(&new),
+        prefixes(&["172.16.0.0/12", "192.168.0.0/24"])
+    );
+    assert!(old.forwarding_diff(&old).is_empty());
+    assert_eq!(
+        old.forwarding_diff(&Ptree::new()),
+        prefixes(&["10.0.0.0/8", "192.168.0.0/24"])
+    );
+}
+
+#[test]
+fn diff_merges_siblings() {
+    let mut old = Ptree::<Ipv4Net, i32>::new();
+    route_ipv4_add(&mut old, "0.0.0.0/0", 1);
+
+    let mut new = Ptree::<Ipv4Net, i32>::new();
+    route_ipv4_add(&mut new, "0.0.0.0/0", 1);
+    route_ipv4_add(&mut new, "10.0.0.0/25", 2);
+    route_ipv4_add(&mut new, "10.0.0.128/25", 3);
+
+    assert_eq!(old.forwarding_diff(&new), prefixes(&["10.0.0.0/24"]));
+}
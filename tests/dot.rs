@@ -0,0 +1,60 @@
+use ipnet::Ipv4Net;
+use ptree::*;
+
+fn dot(ptree: &Ptree<Ipv4Net, u32>, within: Option<&str>) -> String {
+    let within: Option<Ipv4Net> = within.map(|s| s.parse().unwrap());
+    let mut out = Vec::new();
+    ptree.write_dot(&mut out, within.as_ref()).unwrap();
+    String::from_utf8(out).unwrap()
+}
+
+#[test]
+fn dot_glue_and_data() {
+    let mut top = Ptree::<Ipv4Net, u32>::new();
+    top.add_str("10.0.0.0/8", 1).unwrap();
+    top.add_str("10.0.0.0/16", 2).unwrap();
+    top.add_str("10.128.0.0/16", 3).unwrap();
+    top.add_str("10.1.0.0/16", 4).unwrap();
+
+    assert_eq!(
+        dot(&top, None),
+        "digraph ptree {\n\
+         \tnode [fontname=\"monospace\"];\n\
+         \tn0 [label=\"10.0.0.0/8\", shape=box];\n\
+         \tn1 [label=\"10.0.0.0/15\", shape=ellipse, style=dashed];\n\
+         \tn0 -> n1 [label=\"0\"];\n\
+         \tn2 [label=\"10.0.0.0/16\", shape=box];\n\
+         \tn1 -> n2 [label=\"0\"];\n\
+         \tn3 [label=\"10.1.0.0/16\", shape=box];\n\
+         \tn1 -> n3 [label=\"1\"];\n\
+         \tn4 [label=\"10.128.0.0/16\", shape=box];\n\
+         \tn0 -> n4 [label=\"1\"];\n\
+         }\n"
+    );
+
+    assert_eq!(
+        dot(&top, Some("10.0.0.0/12")),
+        "digraph ptree {\n\
+         \tnode [fontname=\"monospace\"];\n\
+         \tn0 [label=\"10.0.0.0/15\", shape=ellipse, style=dashed];\n\
+         \tn1 [label=\"10.0.0.0/16\", shape=box];\n\
+         \tn0 -> n1 [label=\"0\"];\n\
+         \tn2 [label=\"10.1.0.0/16\", shape=box];\n\
+         \tn0 -> n2 [label=\"1\"];\n\
+         }\n"
+    );
+}
+
+#[test]
+fn dot_empty() {
+    let mut top = Ptree::<Ipv4Net, u32>::new();
+    assert_eq!(
+        dot(&top, None),
+        "digraph ptree {\n\tnode [fontname=\"monospace\"];\n}\n"
+    );
+    top.add_str("10.0.0.0/8", 1).unwrap();
+    assert_eq!(
+        dot(&top, Some("192.168.0.0/16")),
+        "digraph ptree {\n\tnode [fontname=\"monospace\"];\n}\n"
+    );
+}
@@ -0,0 +1,142 @@
+use ipnet::Ipv4Net;
+use ptree::*;
+use std::fs;
+use std::path::PathBuf;
+
+fn dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("ptree-durable-{}-{}", std::process::id(), name));
+    let _ = fs::remove_dir_all(&dir);
+    dir
+}
+
+fn open(dir: &PathBuf) -> Durable<Ipv4Net, u32> {
+    Durable::open(
+        dir,
+        |d: &u32| d.to_be_bytes().to_vec(),
+        |bytes| Some(u32::from_be_bytes(bytes.try_into().ok()?)),
+    )
+    .unwrap()
+}
+
+fn routes(ptree: &Ptree<Ipv4Net, u32>) -> Vec<(String, u32)> {
+    ptree
+        .iter()
+        .map(|n| (n.prefix.to_string(), n.data.borrow().unwrap()))
+        .collect()
+}
+
+fn prefix(str: &str) -> Ipv4Net {
+    str.parse().unwrap()
+}
+
+#[test]
+fn durable_recovery() {
+    let dir = dir("recovery");
+    let mut table = open(&dir);
+    table.add(&prefix("10.0.0.0/8"), 1).unwrap();
+    table.add(&prefix("10.1.0.0/16"), 2).unwrap();
+    table.add(&prefix("10.2.0.0/16"), 3).unwrap();
+    table.delete(&prefix("10.1.0.0/16")).unwrap();
+    table.add(&prefix("10.0.0.0/8"), 4).unwrap();
+    let expected = routes(table.tree());
+    drop(table);
+
+    let mut table = open(&dir);
+    assert_eq!(routes(table.tree()), expected);
+    table.snapshot().unwrap();
+    assert_eq!(fs::metadata(dir.join("log")).unwrap().len(), 0);
+    table.add(&prefix("192.168.0.0/24"), 5).unwrap();
+    let expected = routes(table.tree());
+    drop(table);
+
+    let table = open(&dir);
+    assert_eq!(routes(table.tree()), expected);
+    assert_eq!(table.tree().len(), 3);
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn durable_snapshot_interval() {
+    let dir = dir("interval");
+    let mut table = open(&dir);
+    table.set_snapshot_interval(10);
+    for i in 0..25u32 {
+        let prefix = Ipv4Net::new(std::net::Ipv4Addr::from(0x0a00_0000 | i << 8), 24).unwrap();
+        table.add(&prefix, i).unwrap();
+    }
+    assert!(dir.join("snapshot").exists());
+    let log = fs::metadata(dir.join("log")).unwrap().len();
+    assert!(log > 0 && log < 10 * 20);
+    let expected = routes(table.tree());
+    drop(table);
+
+    assert_eq!(routes(open(&dir).tree()), expected);
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn durable_torn_record() {
+    let dir = dir("torn");
+    let mut table = open(&dir);
+    table.add(&prefix("10.0.0.0/8"), 1).unwrap();
+    table.add(&prefix("10.1.0.0/16"), 2).unwrap();
+    drop(table);
+
+    // A crash while appending the third record leaves part of it behind.
+    let log = dir.join("log");
+    let bytes = fs::read(&log).unwrap();
+    let record = bytes.len() / 2;
+    let mut torn = bytes.clone();
+    torn.extend_from_slice(&bytes[..record - 3]);
+    fs::write(&log, &torn).unwrap();
+
+    let mut table = open(&dir);
+    assert_eq!(
+        routes(table.tree()),
+        [
+            ("10.0.0.0/8".to_string(), 1),
+            ("10.1.0.0/16".to_string(), 2)
+        ]
+    );
+    assert_eq!(fs::metadata(&log).unwrap().len(), bytes.len() as u64);
+    table.add(&prefix("10.2.0.0/16"), 3).unwrap();
+    drop(table);
+    assert_eq!(open(&dir).tree().len(), 3);
+
+    // A damaged record in the middle of the log is not a torn write.
+    let mut bytes = fs::read(&log).unwrap();
+    bytes[record + 9] ^= 0xff;
+    fs::write(&log, &bytes).unwrap();
+    assert!(matches!(
+        Durable::<Ipv4Net, u32>::open(&dir, |d| d.to_be_bytes().to_vec(), |_| Some(0)),
+        Err(Error::Checksum)
+    ));
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn durable_damaged_length() {
+    let dir = dir("length");
+    let mut table = open(&dir);
+    table.add(&prefix("10.0.0.0/8"), 1).unwrap();
+    table.add(&prefix("10.1.0.0/16"), 2).unwrap();
+    table.add(&prefix("10.2.0.0/16"), 3).unwrap();
+    drop(table);
+
+    // A length running past the end of the log in the first or a middle
+    // record must not be taken for a torn write and truncate the log.
+    let log = dir.join("log");
+    let bytes = fs::read(&log).unwrap();
+    let record = bytes.len() / 3;
+    for at in [0, record] {
+        let mut damaged = bytes.clone();
+        damaged[at + 2] ^= 0x01;
+        fs::write(&log, &damaged).unwrap();
+        assert!(matches!(
+            Durable::<Ipv4Net, u32>::open(&dir, |d| d.to_be_bytes().to_vec(), |_| Some(0)),
+            Err(Error::Checksum)
+        ));
+        assert_eq!(fs::read(&log).unwrap(), damaged);
+    }
+    fs::remove_dir_all(&dir).unwrap();
+}
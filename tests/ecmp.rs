@@ -0,0 +1,95 @@
+use ipnet::Ipv4Net;
+use ptree::*;
+use std::rc::Rc;
+
+fn spread(group: &NexthopGroup<&'static str>, flows: u32) -> Vec<Option<&'static str>> {
+    (0..flows).map(|hash| group.select(hash)).collect()
+}
+
+fn count(selected: &[Option<&str>], member: &str) -> usize {
+    selected.iter().filter(|m| **m == Some(member)).count()
+}
+
+#[test]
+fn ecmp_even_and_weighted() {
+    let group = NexthopGroup::new(120);
+    assert_eq!(group.select(1), None);
+    group.add("a", 1);
+    group.add("b", 1);
+    group.add("c", 1);
+    let selected = spread(&group, 120);
+    assert_eq!(count(&selected, "a"), 40);
+    assert_eq!(count(&selected, "b"), 40);
+    assert_eq!(count(&selected, "c"), 40);
+
+    group.add("a", 4);
+    let selected = spread(&group, 120);
+    assert_eq!(count(&selected, "a"), 80);
+    assert_eq!(count(&selected, "b"), 20);
+    assert_eq!(count(&selected, "c"), 20);
+    assert_eq!(group.members(), [("a", 4), ("b", 1), ("c", 1)]);
+
+    group.add("a", 0);
+    assert_eq!(count(&spread(&group, 120), "b"), 60);
+}
+
+#[test]
+fn ecmp_resilient() {
+    let group = NexthopGroup::new(256);
+    for member in ["a", "b", "c", "d"] {
+        group.add(member, 1);
+    }
+    let before = spread(&group, 10_000);
+
+    // Only the flows of the removed member move.
+    assert!(group.remove(&"c"));
+    assert!(!group.remove(&"c"));
+    let after = spread(&group, 10_000);
+    for (old, new) in before.iter().zip(after.iter()) {
+        if *old == Some("c") {
+            assert_ne!(*new, Some("c"));
+        } else {
+            assert_eq!(old, new);
+        }
+    }
+    assert_eq!(count(&spread(&group, 256), "a"), 86);
+
+    // An added member only takes flows, it does not shuffle the others.
+    group.add("e", 1);
+    let added = spread(&group, 10_000);
+    for (old, new) in after.iter().zip(added.iter()) {
+        assert!(old == new || *new == Some("e"));
+    }
+    assert_eq!(count(&spread(&group, 256), "e"), 64);
+}
+
+#[test]
+fn ecmp_lookup_flow() {
+    let group = Rc::new(NexthopGroup::new(64));
+    group.add("192.0.2.1", 1);
+    group.add("192.0.2.2", 1);
+
+    let mut top = Ptree::<Ipv4Net, Rc<NexthopGroup<&str>>>::new();
+    top.add_str("10.0.0.0/8", group.clone()).unwrap();
+    top.add_str("172.16.0.0/12", group.clone()).unwrap();
+
+    let addr: Ipv4Net = "10.1.2.3/32".parse().unwrap();
+    let first = top.lookup_flow(&addr, 12345);
+    assert!(first.is_some());
+    assert_eq!(top.lookup_flow(&addr, 12345), first);
+    let members: Vec<_> = (0..64).filter_map(|h| top.lookup_flow(&addr, h)).collect();
+    assert!(members.contains(&"192.0.2.1") && members.contains(&"192.0.2.2"));
+
+    // Both prefixes share the group and see the change.
+    group.remove(&"192.0.2.1");
+    let other: Ipv4Net = "172.16.0.1/32".parse().unwrap();
+    for hash in 0..64 {
+        assert_eq!(top.lookup_flow(&addr, hash), Some("192.0.2.2"));
+        assert_eq!(top.lookup_flow(&other, hash), Some("192.0.2.2"));
+    }
+
+    group.remove(&"192.0.2.2");
+    assert!(group.is_empty());
+    assert_eq!(top.lookup_flow(&addr, 1), None);
+    assert_eq!(top.lookup_flow(&"192.168.0.1/32".parse().unwrap(), 1), None);
+}
@@ -0,0 +1,109 @@
+use ipnet::{Ipv4Net, Ipv6Net};
+use ptree::*;
+
+fn table() -> Ptree<Ipv4Net, &'static str> {
+    let mut top = Ptree::new();
+    top.add_str("10.0.0.0/8", "via 192.0.2.1").unwrap();
+    top.add_str("10.1.0.0/16", "via 192.0.2.2").unwrap();
+    top.add_str("192.168.0.0/25", "dev eth0").unwrap();
+    top.add_str("192.168.0.128/25", "dev eth0").unwrap();
+    top
+}
+
+fn output(f: impl FnOnce(&mut Vec<u8>) -> Result<(), Error>) -> String {
+    let mut out = Vec::new();
+    f(&mut out).unwrap();
+    String::from_utf8(out).unwrap()
+}
+
+#[test]
+fn export_nft_set() {
+    let top = table();
+    assert_eq!(
+        output(|w| top.write_nft_set(w, "allow", None)),
+        "set allow {\n\ttype ipv4_addr\n\tflags interval\n\
+         \telements = { 10.0.0.0/8, 192.168.0.0/24 }\n}\n"
+    );
+
+    let within: Ipv4Net = "10.1.0.0/16".parse().unwrap();
+    assert_eq!(
+        output(|w| top.write_nft_set(w, "allow", Some(&within))),
+        "set allow {\n\ttype ipv4_addr\n\tflags interval\n\
+         \telements = { 10.1.0.0/16 }\n}\n"
+    );
+
+    // Like the other writers, entries covering `within` are left out.
+    let within: Ipv4Net = "10.1.0.0/17".parse().unwrap();
+    assert_eq!(
+        output(|w| top.write_nft_set(w, "allow", Some(&within))),
+        "set allow {\n\ttype ipv4_addr\n\tflags interval\n}\n"
+    );
+    assert_eq!(
+        output(|w| top.write_ipset(w, "allow", Some(&within))),
+        "create allow hash:net family inet\n"
+    );
+
+    let empty = Ptree::<Ipv6Net, ()>::new_ipv6();
+    assert_eq!(
+        output(|w| empty.write_nft_set(w, "deny6", None)),
+        "set deny6 {\n\ttype ipv6_addr\n\tflags interval\n}\n"
+    );
+}
+
+#[test]
+fn export_ipset() {
+    let top = table();
+    let within: Ipv4Net = "192.168.0.0/16".parse().unwrap();
+    assert_eq!(
+        output(|w| top.write_ipset(w, "office", Some(&within))),
+        "create office hash:net family inet\n\
+         add office 192.168.0.0/25\n\
+         add office 192.168.0.128/25\n"
+    );
+}
+
+#[test]
+fn export_ip_batch() {
+    let top = table();
+    let within: Ipv4Net = "10.0.0.0/8".parse().unwrap();
+    assert_eq!(
+        output(|w| top.write_ip_batch(w, Some(&within), |d| d.to_string())),
+        "route add 10.0.0.0/8 via 192.0.2.1\n\
+         route add 10.1.0.0/16 via 192.0.2.2\n"
+    );
+}
+
+#[test]
+fn export_prefix_list() {
+    let top = table();
+    assert_eq!(
+        output(|w| top.write_prefix_list(w, "CUSTOMER", 10, 10, None, |d| d.starts_with("via"))),
+        "ip prefix-list CUSTOMER seq 10 permit 10.0.0.0/8\n\
+         ip prefix-list CUSTOMER seq 20 permit 10.1.0.0/16\n\
+         ip prefix-list CUSTOMER seq 30 deny 192.168.0.0/25\n\
+         ip prefix-list CUSTOMER seq 40 deny 192.168.0.128/25\n"
+    );
+
+    let mut top = Ptree::<Ipv6Net, ()>::new_ipv6();
+    top.add_str("2001:db8::/32", ()).unwrap();
+    assert_eq!(
+        output(|w| top.write_prefix_list(w, "V6", 5, 5, None, |_| true)),
+        "ipv6 prefix-list V6 seq 5 permit 2001:db8::/32\n"
+    );
+
+    // The last sequence number can be used, but not one past it.
+    top.add_str("2001:db9::/32", ()).unwrap();
+    let mut out = Vec::new();
+    top.write_prefix_list(&mut out, "V6", u32::MAX - 1, 1, None, |_| true)
+        .unwrap();
+    assert_eq!(
+        String::from_utf8(out).unwrap(),
+        "ipv6 prefix-list V6 seq 4294967294 permit 2001:db8::/32\n\
+         ipv6 prefix-list V6 seq 4294967295 permit 2001:db9::/32\n"
+    );
+    let mut out = Vec::new();
+    assert!(matches!(
+        top.write_prefix_list(&mut out, "V6", u32::MAX, 1, None, |_| true),
+        Err(Error::InvalidData(_))
+    ));
+}
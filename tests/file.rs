@@ -0,0 +1,59 @@
+use ipnet::Ipv4Net;
+use ptree::*;
+
+const ROUTES: &str = "\
+# customer routes
+10.0.0.0/8 1
+
+192.168.0.0/24   3  # office
+10.1.0.0/16 2
+";
+
+#[test]
+fn file_read_write_routes() {
+    let mut top = Ptree::<Ipv4Net, i32>::new();
+    assert_eq!(top.read_routes(ROUTES.as_bytes()).unwrap(), 3);
+    assert_eq!(top.len(), 3);
+
+    let mut out = Vec::new();
+    top.write_routes(&mut out).unwrap();
+    let out = String::from_utf8(out).unwrap();
+    assert_eq!(out, "10.0.0.0/8 1\n10.1.0.0/16 2\n192.168.0.0/24 3\n");
+
+    let mut copy = Ptree::<Ipv4Net, i32>::new();
+    copy.read_routes(out.as_bytes()).unwrap();
+    assert!(copy.forwarding_equivalent(&top));
+}
+
+#[test]
+fn file_read_write_prefixes() {
+    let mut top = Ptree::<Ipv4Net, ()>::new();
+    let input = "10.0.0.0/8\n\n# comment\n172.16.0.0/12\n";
+    assert_eq!(top.read_prefixes(input.as_bytes(), ()).unwrap(), 2);
+
+    let mut out = Vec::new();
+    top.write_prefixes(&mut out).unwrap();
+    assert_eq!(out, b"10.0.0.0/8\n172.16.0.0/12\n");
+}
+
+#[test]
+fn file_line_errors() {
+    let mut top = Ptree::<Ipv4Net, i32>::new();
+
+    let err = top.read_routes("10.0.0.0/8 1\n\n10.0.0.0/33 2\n".as_bytes());
+    let err = err.unwrap_err();
+    assert!(matches!(&err, Error::Line(3, e) if matches!(**e, Error::Parse(_))));
+    assert_eq!(err.to_string(), "line 3: invalid prefix \"10.0.0.0/33\"");
+
+    let err = top.read_routes("10.0.0.0/8 one\n".as_bytes()).unwrap_err();
+    assert!(matches!(&err, Error::Line(1, e) if matches!(**e, Error::InvalidData(_))));
+
+    let err = top
+        .read_routes("# v6\n2001:db8::/32 1\n".as_bytes())
+        .unwrap_err();
+    assert!(matches!(&err, Error::Line(2, e) if matches!(**e, Error::AddressFamilyMismatch(_))));
+
+    top.set_strict(true);
+    let err = top.read_prefixes("10.1.0.0/8\n".as_bytes(), 0).unwrap_err();
+    assert!(matches!(&err, Error::Line(1, e) if matches!(**e, Error::HostBitsSet(_))));
+}
@@ -0,0 +1,42 @@
+use ipnet::Ipv4Net;
+use ptree::*;
+
+fn route_ipv4_add(ptree: &mut Ptree<Ipv4Net, i32>, str: &str, data: i32) {
+    let prefix: Ipv4Net = str.parse().unwrap();
+    ptree.add(&prefix, data);
+}
+
+fn gaps(ptree: &Ptree<Ipv4Net, i32>, str: &str) -> Vec<String> {
+    let prefix: Ipv4Net = str.parse().unwrap();
+    ptree.gaps(&prefix).iter().map(|p| p.to_string()).collect()
+}
+
+#[test]
+fn gaps_under_prefix() {
+    let mut top = Ptree::<Ipv4Net, i32>::new();
+    route_ipv4_add(&mut top, "10.0.0.0/25", 1);
+    route_ipv4_add(&mut top, "10.0.1.0/24", 1);
+    route_ipv4_add(&mut top, "10.0.3.0/24", 1);
+
+    assert_eq!(gaps(&top, "10.0.0.0/22"), ["10.0.0.128/25", "10.0.2.0/24"]);
+    assert_eq!(gaps(&top, "10.0.0.0/24"), ["10.0.0.128/25"]);
+    assert_eq!(gaps(&top, "10.0.2.0/23"), ["10.0.2.0/24"]);
+    assert!(gaps(&top, "10.0.1.0/24").is_empty());
+    assert!(gaps(&top, "10.0.1.128/25").is_empty());
+    assert_eq!(gaps(&top, "192.168.0.0/16"), ["192.168.0.0/16"]);
+}
+
+#[test]
+fn gaps_glue_and_default() {
+    let mut top = Ptree::<Ipv4Net, i32>::new();
+    route_ipv4_add(&mut top, "10.0.0.0/24", 1);
+    route_ipv4_add(&mut top, "10.0.2.0/24", 1);
+    assert_eq!(gaps(&top, "10.0.0.0/23"), ["10.0.1.0/24"]);
+
+    route_ipv4_add(&mut top, "0.0.0.0/0", 0);
+    assert!(gaps(&top, "10.0.0.0/8").is_empty());
+
+    top.delete(&"0.0.0.0/0".parse().unwrap());
+    assert_eq!(gaps(&top, "10.0.0.0/22"), ["10.0.1.0/24", "10.0.3.0/24"]);
+    assert_eq!(gaps(&Ptree::new(), "0.0.0.0/0"), ["0.0.0.0/0"]);
+}
@@ -2,19 +2,13 @@ use ipnet::Ipv4Net;
 use ptree::*;
 
 fn lookup_assert(top: &Ptree<Ipv4Net, i32>, addr: &str, route: &str) {
-    let prefix: Ipv4Net = addr.parse().unwrap();
-    let iter = top.lookup(&prefix);
-    let n = iter.node;
-    // let n = top.route_ipv4_lookup(addr);
+    let n = top.lookup_str(addr).unwrap();
     let p: Ipv4Net = route.parse().unwrap();
     assert_eq!(n.unwrap().prefix, p);
 }
 
 fn lookup_assert_none(top: &Ptree<Ipv4Net, i32>, addr: &str) {
-    let prefix: Ipv4Net = addr.parse().unwrap();
-    let iter = top.lookup(&prefix);
-    let n = iter.node;
-    // let n = top.route_ipv4_lookup(addr);
+    let n = top.lookup_str(addr).unwrap();
     assert!(n.is_none());
 }
 
@@ -36,13 +30,11 @@ fn lookup_test(top: &Ptree<Ipv4Net, i32>) {
 }
 
 fn route_ipv4_add(ptree: &mut Ptree<Ipv4Net, i32>, str: &str, data: i32) {
-    let prefix: Ipv4Net = str.parse().unwrap();
-    ptree.add(&prefix, data);
+    ptree.add_str(str, data).unwrap();
 }
 
 fn route_ipv4_delete(ptree: &mut Ptree<Ipv4Net, i32>, str: &str) {
-    let prefix: Ipv4Net = str.parse().unwrap();
-    ptree.delete(&prefix);
+    ptree.delete_str(str).unwrap();
 }
 
 #[test]
@@ -56,7 +48,7 @@ fn ipv4_lookup_reverse_test() {
     route_ipv4_add(&mut top, "10.0.0.0/29", 29);
     route_ipv4_add(&mut top, "10.0.0.0/28", 28);
 
-    lookup_test(&mut top);
+    lookup_test(&top);
 }
 
 #[test]
@@ -202,3 +194,65 @@ fn ipv4_delete_table_default() {
     route_ipv4_delete(&mut top, "0.0.0.0/4");
     assert_eq!(top.iter().count(), 1);
 }
+
+#[test]
+fn ipv4_host_bits_normalized() {
+    let mut top = Ptree::<Ipv4Net, i32>::new();
+
+    route_ipv4_add(&mut top, "10.1.1.1/8", 8);
+    route_ipv4_add(&mut top, "10.0.0.0/8", 9);
+    assert_eq!(top.len(), 1);
+    assert!(top.validate().is_ok());
+
+    let p: Ipv4Net = "10.0.0.0/8".parse().unwrap();
+    let n = top.lookup_exact(&"10.255.0.0/8".parse().unwrap()).node;
+    assert_eq!(n.unwrap().prefix, p);
+    let n = top.find(&"10.9.9.9/8".parse().unwrap()).node;
+    assert_eq!(n.unwrap().prefix, p);
+    lookup_assert_none(&top, "11.0.0.0/8");
+
+    route_ipv4_delete(&mut top, "10.2.3.4/8");
+    assert!(top.is_empty());
+}
+
+#[test]
+fn ipv4_strict_try_add() {
+    let mut top = Ptree::<Ipv4Net, i32>::new();
+    let p: Ipv4Net = "10.1.1.1/8".parse().unwrap();
+
+    assert!(top.try_add(&p, 8).is_ok());
+    assert_eq!(top.len(), 1);
+
+    top.set_strict(true);
+    assert!(top.is_strict());
+    let err = top.try_add(&p, 8).unwrap_err();
+    assert!(matches!(err, Error::HostBitsSet(_)));
+    assert_eq!(err.to_string(), "host bits set in 10.1.1.1/8");
+    assert!(top.try_add(&"10.0.0.0/8".parse().unwrap(), 8).is_ok());
+    assert_eq!(top.len(), 1);
+}
+
+#[test]
+fn ipv4_str_api() {
+    let mut top = Ptree::<Ipv4Net, i32>::new();
+
+    route_ipv4_add(&mut top, "10.0.0.0/8", 8);
+    lookup_assert(&top, "10.1.2.3", "10.0.0.0/8");
+    assert!(top.lookup_exact_str("10.0.0.0/8").unwrap().is_some());
+    assert!(top.lookup_exact_str("10.0.0.0/9").unwrap().is_none());
+    assert!(top.find_str("10.0.0.0/8").unwrap().is_some());
+
+    let err = top.add_str("10.0.0.0/33", 0).unwrap_err();
+    assert!(matches!(err, Error::Parse(_)));
+    let err = top.lookup_str("ten").unwrap_err();
+    assert!(matches!(err, Error::Parse(_)));
+    let err = top.add_str("2001:db8::/32", 0).unwrap_err();
+    assert!(matches!(err, Error::AddressFamilyMismatch(_)));
+    let err = top.lookup_str("2001:db8::1").unwrap_err();
+    assert!(matches!(err, Error::AddressFamilyMismatch(_)));
+
+    top.set_strict(true);
+    let err = top.add_str("10.1.1.1/16", 0).unwrap_err();
+    assert!(matches!(err, Error::HostBitsSet(_)));
+    assert_eq!(top.len(), 1);
+}
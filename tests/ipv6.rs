@@ -0,0 +1,15 @@
+use ipnet::Ipv6Net;
+use ptree::*;
+
+#[test]
+fn ipv6_str_api() {
+    let mut top = Ptree::<Ipv6Net, i32>::new_ipv6();
+
+    top.add_str("2001:db8::/32", 32).unwrap();
+    let n = top.lookup_str("2001:db8::1").unwrap().unwrap();
+    assert_eq!(n.prefix.to_string(), "2001:db8::/32");
+    let err = top.lookup_str("10.0.0.1").unwrap_err();
+    assert!(matches!(err, Error::AddressFamilyMismatch(_)));
+    top.delete_str("2001:db8::/32").unwrap();
+    assert!(top.is_empty());
+}
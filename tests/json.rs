@@ -0,0 +1,89 @@
+use ipnet::Ipv4Net;
+use ptree::*;
+
+fn table() -> Ptree<Ipv4Net, u32> {
+    let mut top = Ptree::new();
+    top.add_str("10.0.0.0/8", 1).unwrap();
+    top.add_str("10.1.0.0/16", 2).unwrap();
+    top.add_str("10.1.2.0/24", 3).unwrap();
+    top.add_str("10.128.0.0/16", 4).unwrap();
+    top
+}
+
+#[cfg(feature = "json")]
+fn output(f: impl FnOnce(&mut Vec<u8>) -> Result<(), Error>) -> String {
+    let mut out = Vec::new();
+    f(&mut out).unwrap();
+    let json = String::from_utf8(out).unwrap();
+    serde_json::from_str::<serde_json::Value>(&json).unwrap();
+    json
+}
+
+#[cfg(feature = "json")]
+#[test]
+fn json_flat() {
+    let top = table();
+    assert_eq!(
+        output(|w| top.write_json(w, |d| *d)),
+        r#"[{"prefix":"10.0.0.0/8","data":1},{"prefix":"10.1.0.0/16","data":2},{"prefix":"10.1.2.0/24","data":3},{"prefix":"10.128.0.0/16","data":4}]"#
+    );
+    assert_eq!(
+        output(|w| Ptree::<Ipv4Net, u32>::new().write_json(w, |d| *d)),
+        "[]"
+    );
+}
+
+#[cfg(feature = "json")]
+#[test]
+fn json_tree() {
+    let mut top = Ptree::<Ipv4Net, &str>::new();
+    top.add_str("10.0.0.0/16", "a").unwrap();
+    top.add_str("10.128.0.0/16", "b\"").unwrap();
+    top.add_str("10.128.1.0/24", "c\u{0}\t\u{1f}").unwrap();
+    assert_eq!(
+        output(|w| top.write_json_tree(w, |d| *d)),
+        r#"{"prefix":"10.0.0.0/8","left":{"prefix":"10.0.0.0/16","data":"a"},"right":{"prefix":"10.128.0.0/16","data":"b\"","left":{"prefix":"10.128.1.0/24","data":"c\u0000\t\u001f"}}}"#
+    );
+    assert_eq!(
+        output(|w| Ptree::<Ipv4Net, &str>::new().write_json_tree(w, |d| *d)),
+        "null"
+    );
+}
+
+#[cfg(feature = "json")]
+#[test]
+fn json_lookup() {
+    let top = table();
+    let data = |d: &u32| serde_json::json!({ "id": d });
+
+    let prefix: Ipv4Net = "10.1.2.3/32".parse().unwrap();
+    assert_eq!(
+        output(|w| top.write_lookup_json(w, &prefix, data)),
+        r#"{"query":"10.1.2.3/32","match":{"prefix":"10.1.2.0/24","data":{"id":3}},"covering":[{"prefix":"10.0.0.0/8","data":{"id":1}},{"prefix":"10.1.0.0/16","data":{"id":2}}]}"#
+    );
+
+    let prefix: Ipv4Net = "10.2.0.0/16".parse().unwrap();
+    assert_eq!(
+        output(|w| top.write_lookup_json(w, &prefix, data)),
+        r#"{"query":"10.2.0.0/16","match":{"prefix":"10.0.0.0/8","data":{"id":1}},"covering":[]}"#
+    );
+
+    let prefix: Ipv4Net = "192.168.0.1/24".parse().unwrap();
+    assert_eq!(
+        output(|w| top.write_lookup_json(w, &prefix, data)),
+        r#"{"query":"192.168.0.0/24","match":null,"covering":[]}"#
+    );
+}
+
+#[test]
+fn covering_chain() {
+    let top = table();
+    let prefix: Ipv4Net = "10.1.2.128/25".parse().unwrap();
+    let chain: Vec<String> = top
+        .covering(&prefix)
+        .iter()
+        .map(|n| n.prefix.to_string())
+        .collect();
+    assert_eq!(chain, ["10.0.0.0/8", "10.1.0.0/16", "10.1.2.0/24"]);
+    assert!(top.covering(&"11.0.0.0/8".parse().unwrap()).is_empty());
+}
@@ -0,0 +1,93 @@
+use ipnet::{Ipv4Net, Ipv6Net};
+use ptree::*;
+use std::fs::File;
+use std::io::BufReader;
+
+fn open(path: &str) -> BufReader<File> {
+    BufReader::new(File::open(path).unwrap())
+}
+
+fn peer_asns(rib: &Rib<impl Prefix>) -> Option<Vec<u32>> {
+    Some(
+        rib.entries
+            .iter()
+            .map(|e| e.peer.as_ref().unwrap().asn)
+            .collect(),
+    )
+}
+
+#[test]
+fn mrt_read_ipv4() {
+    let mut top = Ptree::<Ipv4Net, Vec<u32>>::new();
+    let count = top
+        .read_mrt(open("tests/data/rib-small.mrt"), peer_asns)
+        .unwrap();
+    assert_eq!(count, 3);
+
+    let n = top.lookup_str("10.1.2.3").unwrap().unwrap();
+    assert_eq!(n.prefix.to_string(), "10.0.0.0/8");
+    assert_eq!(*n.data.borrow(), Some(vec![64500, 4200000000]));
+
+    let n = top.lookup_exact_str("203.0.113.128/25").unwrap().unwrap();
+    assert_eq!(*n.data.borrow(), Some(vec![64500]));
+}
+
+#[test]
+fn mrt_read_ipv6() {
+    let mut top = Ptree::<Ipv6Net, usize>::new_ipv6();
+    let count = top
+        .read_mrt(open("tests/data/rib-small.mrt"), |rib| {
+            Some(rib.entries.len())
+        })
+        .unwrap();
+    assert_eq!(count, 2);
+
+    let n = top.lookup_str("2001:db8:1::1").unwrap().unwrap();
+    assert_eq!(n.prefix.to_string(), "2001:db8:1::/48");
+    let n = top.lookup_str("2001:db8:2::1").unwrap().unwrap();
+    assert_eq!(*n.data.borrow(), Some(2));
+}
+
+#[test]
+fn mrt_reader_entries() {
+    let mut reader = MrtReader::<_, Ipv4Net>::new(open("tests/data/rib-small.mrt"));
+    let rib = reader.next().unwrap().unwrap();
+    assert_eq!(rib.sequence, 0);
+    assert_eq!(rib.prefix.to_string(), "10.0.0.0/8");
+    assert_eq!(rib.entries.len(), 2);
+    assert_eq!(rib.entries[1].peer_index, 1);
+    assert_eq!(rib.entries[1].originated_time, 1699999900);
+    assert_eq!(rib.entries[1].attributes.len(), 13);
+
+    let peers = reader.peers();
+    assert_eq!(peers.len(), 2);
+    assert_eq!(peers[0].addr.to_string(), "192.0.2.1");
+    assert_eq!(peers[1].addr.to_string(), "2001:db8::2");
+    assert_eq!(peers[1].bgp_id.to_string(), "192.0.2.2");
+
+    assert_eq!(reader.count(), 2);
+}
+
+#[test]
+fn mrt_truncated() {
+    let mut top = Ptree::<Ipv6Net, usize>::new_ipv6();
+    let err = top
+        .read_mrt(open("tests/data/rib-truncated.mrt"), |_| Some(0))
+        .unwrap_err();
+    assert!(matches!(err, Error::Truncated));
+
+    let mut reader = MrtReader::<_, Ipv4Net>::new(open("tests/data/rib-truncated.mrt"));
+    assert!(reader.next().unwrap().is_ok());
+    assert!(reader.next().unwrap().is_ok());
+    assert!(reader.next().unwrap().is_ok());
+    assert!(matches!(reader.next(), Some(Err(Error::Truncated))));
+}
+
+#[test]
+fn mrt_huge_length() {
+    // A TABLE_DUMP_V2 header claiming a 4 GiB body followed by 4 bytes.
+    let mut bytes = vec![0, 0, 0, 0, 0, 13, 0, 2, 0xff, 0xff, 0xff, 0xff];
+    bytes.extend_from_slice(&[0, 0, 0, 1]);
+    let mut reader = MrtReader::<_, Ipv4Net>::new(&bytes[..]);
+    assert!(matches!(reader.next(), Some(Err(Error::Truncated))));
+}
@@ -0,0 +1,89 @@
+use ipnet::Ipv4Net;
+use ptree::*;
+
+fn p(str: &str) -> Ipv4Net {
+    str.parse().unwrap()
+}
+
+fn tracker() -> NexthopTracker<Ipv4Net, Option<Ipv4Net>> {
+    NexthopTracker::new(|nexthop: &Option<Ipv4Net>| *nexthop)
+}
+
+#[test]
+fn nht_recursive() {
+    let mut nht = tracker();
+    assert_eq!(
+        nht.track(&p("192.0.2.1/32")),
+        Resolution::Unresolved(vec![])
+    );
+
+    // BGP route via 192.0.2.1, resolved through an IGP route to a
+    // connected subnet.
+    let notes = nht.add(&p("10.0.0.0/8"), None);
+    assert!(notes.is_empty());
+    let notes = nht.add(&p("192.0.2.0/24"), Some(p("10.0.0.1/32")));
+    assert_eq!(
+        notes,
+        [Notification {
+            addr: p("192.0.2.1/32"),
+            old: Resolution::Unresolved(vec![]),
+            new: Resolution::Resolved(vec![p("192.0.2.0/24"), p("10.0.0.0/8")]),
+        }]
+    );
+
+    // A more specific route for the intermediate next hop.
+    let notes = nht.add(&p("10.0.0.0/24"), None);
+    assert_eq!(notes.len(), 1);
+    assert_eq!(
+        notes[0].new,
+        Resolution::Resolved(vec![p("192.0.2.0/24"), p("10.0.0.0/24")])
+    );
+
+    // Unrelated changes are not reported.
+    assert!(nht.add(&p("172.16.0.0/12"), None).is_empty());
+    assert!(nht.add(&p("10.0.1.0/24"), None).is_empty());
+
+    // Replacing a route of the chain is reported even if the chain stays.
+    let notes = nht.add(&p("10.0.0.0/24"), None);
+    assert_eq!(notes.len(), 1);
+    assert_eq!(notes[0].old, notes[0].new);
+
+    let notes = nht.delete(&p("10.0.0.0/24"));
+    assert_eq!(
+        notes[0].new,
+        Resolution::Resolved(vec![p("192.0.2.0/24"), p("10.0.0.0/8")])
+    );
+    let notes = nht.delete(&p("10.0.0.0/8"));
+    assert_eq!(
+        notes[0].new,
+        Resolution::Unresolved(vec![p("192.0.2.0/24")])
+    );
+    assert!(!nht.resolution(&p("192.0.2.1/32")).unwrap().is_resolved());
+
+    assert!(nht.untrack(&p("192.0.2.1/32")));
+    assert!(nht.add(&p("10.0.0.0/8"), None).is_empty());
+    assert_eq!(nht.resolution(&p("192.0.2.1/32")), None);
+}
+
+#[test]
+fn nht_loop() {
+    let mut nht = tracker();
+    nht.add(&p("10.0.0.0/8"), Some(p("172.16.0.1/32")));
+    nht.add(&p("172.16.0.0/12"), Some(p("10.0.0.1/32")));
+    assert_eq!(
+        nht.track(&p("10.1.1.1/32")),
+        Resolution::Loop(vec![p("10.0.0.0/8"), p("172.16.0.0/12"), p("10.0.0.0/8")])
+    );
+
+    // A route resolving through itself.
+    nht.add(&p("192.0.2.0/24"), Some(p("192.0.2.1/32")));
+    assert!(matches!(nht.track(&p("192.0.2.7/32")), Resolution::Loop(_)));
+
+    // A route for the next hop breaks the loop.
+    let notes = nht.add(&p("172.16.0.0/16"), None);
+    assert_eq!(notes.len(), 1);
+    assert_eq!(
+        notes[0].new,
+        Resolution::Resolved(vec![p("10.0.0.0/8"), p("172.16.0.0/16")])
+    );
+}
@@ -0,0 +1,124 @@
+use ipnet::{Ipv4Net, Ipv6Net};
+use ptree::*;
+
+fn nlri<P: std::str::FromStr>(path_id: Option<u32>, str: &str) -> Nlri<P>
+where
+    P::Err: std::fmt::Debug,
+{
+    Nlri {
+        path_id,
+        prefix: str.parse().unwrap(),
+    }
+}
+
+#[test]
+fn nlri_encode() {
+    let mut out = Vec::new();
+    nlri::<Ipv4Net>(None, "10.0.0.0/8").encode(&mut out);
+    nlri::<Ipv4Net>(None, "192.168.1.128/25").encode(&mut out);
+    nlri::<Ipv4Net>(None, "0.0.0.0/0").encode(&mut out);
+    nlri::<Ipv4Net>(None, "10.1.2.3/17").encode(&mut out);
+    assert_eq!(out, [8, 10, 25, 192, 168, 1, 128, 0, 17, 10, 1, 0]);
+
+    let mut out = Vec::new();
+    nlri::<Ipv6Net>(Some(7), "2001:db8::/33").encode(&mut out);
+    assert_eq!(out, [0, 0, 0, 7, 33, 0x20, 0x01, 0x0d, 0xb8, 0]);
+}
+
+#[test]
+fn nlri_decode() {
+    let bytes = [8, 10, 25, 192, 168, 1, 128, 0, 17, 10, 1, 0];
+    let decoded: Vec<Nlri<Ipv4Net>> = NlriReader::new(&bytes, false)
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(
+        decoded,
+        [
+            nlri(None, "10.0.0.0/8"),
+            nlri(None, "192.168.1.128/25"),
+            nlri(None, "0.0.0.0/0"),
+            nlri(None, "10.1.0.0/17"),
+        ]
+    );
+
+    let mut bytes = Vec::new();
+    let expected = [
+        nlri::<Ipv6Net>(Some(1), "2001:db8::/32"),
+        nlri::<Ipv6Net>(Some(2), "2001:db8::/32"),
+        nlri::<Ipv6Net>(Some(2), "::/0"),
+        nlri::<Ipv6Net>(Some(u32::MAX), "2001:db8:1:2::1/128"),
+    ];
+    for nlri in expected.iter() {
+        nlri.encode(&mut bytes);
+    }
+    let decoded: Vec<Nlri<Ipv6Net>> = NlriReader::new(&bytes, true)
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(decoded, expected);
+    assert_eq!(NlriReader::<Ipv6Net>::new(&[], true).count(), 0);
+}
+
+#[test]
+fn nlri_malformed() {
+    let mut reader = NlriReader::<Ipv4Net>::new(&[8, 10, 33, 1, 2, 3, 4, 5], false);
+    assert!(reader.next().unwrap().is_ok());
+    assert!(matches!(
+        reader.next(),
+        Some(Err(Error::InvalidPrefixLength(33)))
+    ));
+    assert!(reader.next().is_none());
+
+    let mut reader = NlriReader::<Ipv4Net>::new(&[24, 10, 0], false);
+    assert!(matches!(reader.next(), Some(Err(Error::Truncated))));
+    let mut reader = NlriReader::<Ipv4Net>::new(&[0, 0, 1], true);
+    assert!(matches!(reader.next(), Some(Err(Error::Truncated))));
+    let mut reader = NlriReader::<Ipv6Net>::new(&[129], false);
+    assert!(matches!(
+        reader.next(),
+        Some(Err(Error::InvalidPrefixLength(129)))
+    ));
+}
+
+#[test]
+fn nlri_apply() {
+    let mut top = Ptree::<Ipv4Net, u32>::new();
+    top.add_str("10.0.0.0/8", 1).unwrap();
+    top.add_str("10.1.0.0/16", 2).unwrap();
+
+    let withdrawn = [16, 10, 1];
+    let announced = [24, 192, 168, 1, 8, 11];
+    let counts = top
+        .apply_nlri(&withdrawn, &announced, false, |nlri| {
+            nlri.prefix.prefix_len() as u32
+        })
+        .unwrap();
+    assert_eq!(counts, (1, 2));
+    let routes: Vec<(String, u32)> = top
+        .iter()
+        .map(|n| (n.prefix.to_string(), n.data.borrow().unwrap()))
+        .collect();
+    assert_eq!(
+        routes,
+        [
+            ("10.0.0.0/8".to_string(), 1),
+            ("11.0.0.0/8".to_string(), 8),
+            ("192.168.1.0/24".to_string(), 24)
+        ]
+    );
+
+    // A malformed field leaves the table alone.
+    assert!(top
+        .apply_nlri(&[8, 10], &[24, 192, 168], false, |_| 0)
+        .is_err());
+    assert_eq!(top.len(), 3);
+
+    let announced = [0, 0, 0, 5, 16, 172, 16];
+    top.apply_nlri(&[], &announced, true, |nlri| nlri.path_id.unwrap())
+        .unwrap();
+    assert_eq!(
+        top.lookup_exact_str("172.16.0.0/16")
+            .unwrap()
+            .map(|n| n.data.borrow().unwrap()),
+        Some(5)
+    );
+}
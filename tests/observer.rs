@@ -0,0 +1,133 @@
+use ipnet::Ipv4Net;
+use ptree::*;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+struct Log(Rc<RefCell<Vec<String>>>);
+
+impl Observer<Ipv4Net, u32> for Log {
+    fn added(&mut self, prefix: &Ipv4Net, data: &u32) {
+        self.0.borrow_mut().push(format!("add {} {}", prefix, data));
+    }
+
+    fn replaced(&mut self, prefix: &Ipv4Net, old: &u32, new: &u32) {
+        self.0
+            .borrow_mut()
+            .push(format!("replace {} {} {}", prefix, old, new));
+    }
+
+    fn removed(&mut self, prefix: &Ipv4Net, data: &u32) {
+        self.0
+            .borrow_mut()
+            .push(format!("remove {} {}", prefix, data));
+    }
+}
+
+fn observed() -> (Ptree<Ipv4Net, u32>, Rc<RefCell<Vec<String>>>, ObserverId) {
+    let log = Rc::new(RefCell::new(Vec::new()));
+    let mut top = Ptree::new();
+    let id = top.add_observer(Box::new(Log(log.clone())));
+    (top, log, id)
+}
+
+fn take(log: &Rc<RefCell<Vec<String>>>) -> Vec<String> {
+    log.borrow_mut().drain(..).collect()
+}
+
+#[test]
+fn observer_add_replace_delete() {
+    let (mut top, log, _) = observed();
+    top.add_str("10.0.0.0/8", 1).unwrap();
+    top.add_str("10.1.0.0/16", 2).unwrap();
+    top.add_str("10.128.0.0/16", 3).unwrap();
+    top.add_str("10.0.0.0/8", 4).unwrap();
+    assert_eq!(
+        take(&log),
+        [
+            "add 10.0.0.0/8 1",
+            "add 10.1.0.0/16 2",
+            "add 10.128.0.0/16 3",
+            "replace 10.0.0.0/8 1 4"
+        ]
+    );
+
+    // A node with two children stays as glue, erasing a leaf also splices
+    // out the glue above it once that has a single child left, and missing
+    // prefixes change nothing.
+    top.delete_str("10.0.0.0/8").unwrap();
+    top.delete_str("10.1.0.0/16").unwrap();
+    assert_eq!(top.structure().nodes, 1);
+    top.delete_str("10.1.0.0/16").unwrap();
+    top.delete_str("10.0.0.0/8").unwrap();
+    top.delete_str("10.128.0.0/16").unwrap();
+    assert_eq!(
+        take(&log),
+        [
+            "remove 10.0.0.0/8 4",
+            "remove 10.1.0.0/16 2",
+            "remove 10.128.0.0/16 3"
+        ]
+    );
+    assert!(top.is_empty());
+    assert!(top.validate().is_ok());
+}
+
+#[test]
+fn observer_derived_operations() {
+    let (mut top, log, _) = observed();
+    top.extend([
+        ("10.0.0.0/25".parse().unwrap(), 1),
+        ("10.0.0.128/25".parse().unwrap(), 1),
+    ]);
+    let prefixes: Vec<Ipv4Net> = vec!["10.0.0.0/25".parse().unwrap()];
+    let text = "10.0.0.0/25 7\n10.0.1.0/24 8\n";
+    top.read_routes(text.as_bytes()).unwrap();
+    top.delete(&prefixes[0]);
+    assert_eq!(
+        take(&log),
+        [
+            "add 10.0.0.0/25 1",
+            "add 10.0.0.128/25 1",
+            "replace 10.0.0.0/25 1 7",
+            "add 10.0.1.0/24 8",
+            "remove 10.0.0.0/25 7"
+        ]
+    );
+}
+
+#[test]
+fn observer_remove() {
+    let (mut top, log, id) = observed();
+    let other = Rc::new(RefCell::new(Vec::new()));
+    let other_id = top.add_observer(Box::new(Log(other.clone())));
+    assert_ne!(id, other_id);
+
+    top.add_str("10.0.0.0/8", 1).unwrap();
+    assert!(top.remove_observer(id).is_some());
+    assert!(top.remove_observer(id).is_none());
+    top.add_str("10.0.0.0/8", 2).unwrap();
+    assert_eq!(take(&log), ["add 10.0.0.0/8 1"]);
+    assert_eq!(take(&other), ["add 10.0.0.0/8 1", "replace 10.0.0.0/8 1 2"]);
+}
+
+#[test]
+fn observer_node_changes_not_reported() {
+    let (mut top, log, _) = observed();
+    let prefix: Ipv4Net = "10.0.0.0/8".parse().unwrap();
+    top.try_add(&prefix, 1).unwrap();
+    assert_eq!(take(&log), ["add 10.0.0.0/8 1"]);
+
+    // Only changes made through the table are reported.
+    let node = top.lookup_exact(&prefix).node.unwrap();
+    node.set_data(2);
+    node.data.replace(Some(3));
+    top.insert(&"10.1.0.0/16".parse().unwrap())
+        .node
+        .unwrap()
+        .set_data(4);
+    node.unset_data();
+    assert!(take(&log).is_empty());
+
+    top.delete(&"10.1.0.0/16".parse().unwrap());
+    assert_eq!(take(&log), ["remove 10.1.0.0/16 4"]);
+}
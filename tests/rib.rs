@@ -0,0 +1,111 @@
+use ipnet::Ipv4Net;
+use ptree::*;
+
+fn route(protocol: &str, distance: u32, metric: u32) -> Route {
+    Route {
+        protocol: protocol.to_string(),
+        distance: Some(distance),
+        metric: Some(metric),
+        nexthops: Vec::new(),
+    }
+}
+
+fn prefix(str: &str) -> Ipv4Net {
+    str.parse().unwrap()
+}
+
+fn protocols(rib: &MultiRib<Ipv4Net, &str, Route>, str: &str) -> Vec<String> {
+    rib.candidates(&prefix(str))
+        .iter()
+        .map(|(source, route)| format!("{}:{}", source, route.protocol))
+        .collect()
+}
+
+#[test]
+fn rib_best_path() {
+    let mut rib = MultiRib::<Ipv4Net, &str, Route>::new(Route::preference);
+    assert!(rib
+        .insert(&prefix("10.0.0.0/8"), "peer1", route("bgp", 20, 0))
+        .is_none());
+    rib.insert(&prefix("10.0.0.0/8"), "ospfd", route("ospf", 110, 20));
+    rib.insert(&prefix("10.0.0.0/8"), "peer2", route("bgp", 20, 0));
+    rib.insert(&prefix("10.1.0.0/16"), "static", route("static", 1, 0));
+
+    assert_eq!(rib.len(), 2);
+    assert_eq!(
+        protocols(&rib, "10.0.0.0/8"),
+        ["peer1:bgp", "peer2:bgp", "ospfd:ospf"]
+    );
+    assert_eq!(
+        rib.lookup(&prefix("10.2.3.4/32")),
+        Some((prefix("10.0.0.0/8"), route("bgp", 20, 0)))
+    );
+    assert_eq!(
+        rib.lookup(&prefix("10.1.3.4/32"))
+            .map(|(p, r)| (p, r.protocol)),
+        Some((prefix("10.1.0.0/16"), "static".to_string()))
+    );
+
+    // A worse route from the current best source lets the next one win.
+    let replaced = rib.insert(&prefix("10.0.0.0/8"), "peer1", route("bgp", 200, 0));
+    assert_eq!(replaced, Some(route("bgp", 20, 0)));
+    assert_eq!(
+        protocols(&rib, "10.0.0.0/8"),
+        ["peer2:bgp", "ospfd:ospf", "peer1:bgp"]
+    );
+    assert_eq!(
+        rib.lookup_exact(&prefix("10.0.0.0/8")),
+        Some(route("bgp", 20, 0))
+    );
+    assert_eq!(rib.lookup_exact(&prefix("10.0.0.0/9")), None);
+}
+
+#[test]
+fn rib_remove() {
+    let mut rib = MultiRib::<Ipv4Net, &str, Route>::new(Route::preference);
+    rib.insert(&prefix("10.0.0.0/8"), "peer1", route("bgp", 20, 0));
+    rib.insert(&prefix("10.0.0.0/8"), "ospfd", route("ospf", 110, 20));
+    rib.insert(&prefix("10.1.0.0/16"), "peer1", route("bgp", 20, 0));
+
+    assert_eq!(
+        rib.remove(&prefix("10.0.0.0/8"), &"peer1"),
+        Some(route("bgp", 20, 0))
+    );
+    assert_eq!(rib.remove(&prefix("10.0.0.0/8"), &"peer1"), None);
+    assert_eq!(protocols(&rib, "10.0.0.0/8"), ["ospfd:ospf"]);
+    assert_eq!(
+        rib.lookup(&prefix("10.1.2.0/24")).map(|(p, _)| p),
+        Some(prefix("10.1.0.0/16"))
+    );
+
+    assert_eq!(rib.remove_source(&"peer1"), 1);
+    assert_eq!(rib.len(), 1);
+    assert_eq!(
+        rib.lookup(&prefix("10.1.2.0/24")).map(|(p, _)| p),
+        Some(prefix("10.0.0.0/8"))
+    );
+    rib.remove(&prefix("10.0.0.0/8"), &"ospfd");
+    assert!(rib.is_empty());
+    assert!(rib.tree().validate().is_ok());
+}
+
+#[test]
+fn rib_custom_compare() {
+    // Prefer the highest value, as for BGP local preference.
+    let mut rib = MultiRib::<Ipv4Net, u32, u32>::new(|a: &u32, b: &u32| b.cmp(a));
+    rib.insert(&prefix("10.0.0.0/8"), 1, 100);
+    rib.insert(&prefix("10.0.0.0/8"), 2, 300);
+    rib.insert(&prefix("10.0.0.0/8"), 3, 200);
+    assert_eq!(rib.lookup_exact(&prefix("10.0.0.0/8")), Some(300));
+
+    let node = rib.tree().lookup_exact(&prefix("10.0.0.0/8")).node.unwrap();
+    let data = node.data.borrow();
+    let candidates = data.as_ref().unwrap();
+    assert_eq!(candidates.len(), 3);
+    assert_eq!(candidates.best(), Some((&2, &300)));
+    assert_eq!(candidates.get(&1), Some(&100));
+    assert_eq!(
+        candidates.iter().map(|(s, _)| *s).collect::<Vec<_>>(),
+        [2, 3, 1]
+    );
+}
@@ -0,0 +1,65 @@
+#![cfg(feature = "serde")]
+
+use ipnet::{Ipv4Net, Ipv6Net};
+use ptree::*;
+
+fn routes<P: Prefix + Copy + ToString, D: Clone>(ptree: &Ptree<P, D>) -> Vec<(String, D)> {
+    ptree
+        .iter()
+        .map(|n| (n.prefix.to_string(), n.data.borrow().clone().unwrap()))
+        .collect()
+}
+
+fn table() -> Ptree<Ipv4Net, u32> {
+    let mut top = Ptree::new();
+    top.add_str("10.0.0.0/8", 1).unwrap();
+    top.add_str("10.1.0.0/16", 2).unwrap();
+    top.add_str("10.2.0.0/16", 3).unwrap();
+    top.add_str("192.168.0.0/24", 4).unwrap();
+    top
+}
+
+#[test]
+fn serde_json_round_trip() {
+    let top = table();
+    let json = serde_json::to_string(&top).unwrap();
+    assert_eq!(
+        json,
+        r#"[["10.0.0.0/8",1],["10.1.0.0/16",2],["10.2.0.0/16",3],["192.168.0.0/24",4]]"#
+    );
+
+    let back: Ptree<Ipv4Net, u32> = serde_json::from_str(&json).unwrap();
+    assert_eq!(routes(&back), routes(&top));
+    assert_eq!(back.len(), top.len());
+    assert!(back.validate().is_ok());
+
+    let empty: Ptree<Ipv4Net, u32> = serde_json::from_str("[]").unwrap();
+    assert!(empty.is_empty());
+    assert!(serde_json::from_str::<Ptree<Ipv4Net, u32>>(r#"[["10.0.0.0/33",1]]"#).is_err());
+}
+
+#[test]
+fn serde_bincode_round_trip() {
+    let top = table();
+    let bytes = bincode::serialize(&top).unwrap();
+    let back: Ptree<Ipv4Net, u32> = bincode::deserialize(&bytes).unwrap();
+    assert_eq!(routes(&back), routes(&top));
+
+    let mut top = Ptree::<Ipv6Net, String>::new_ipv6();
+    top.add_str("2001:db8::/32", "a".to_string()).unwrap();
+    top.add_str("2001:db8:1::/48", "b".to_string()).unwrap();
+    let bytes = bincode::serialize(&top).unwrap();
+    let back: Ptree<Ipv6Net, String> = bincode::deserialize(&bytes).unwrap();
+    assert_eq!(routes(&back), routes(&top));
+}
+
+#[test]
+fn serde_bincode_data_set_on_nodes() {
+    let mut top = table();
+    let prefix: Ipv4Net = "172.16.0.0/12".parse().unwrap();
+    top.insert(&prefix).node.unwrap().set_data(5);
+    let bytes = bincode::serialize(&top).unwrap();
+    let back: Ptree<Ipv4Net, u32> = bincode::deserialize(&bytes).unwrap();
+    assert_eq!(routes(&back), routes(&top));
+    assert_eq!(back.len(), 5);
+}
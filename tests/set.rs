@@ -0,0 +1,91 @@
+use ipnet::Ipv4Net;
+use ptree::*;
+
+fn set(list: &[&str]) -> PrefixSet<Ipv4Net> {
+    list.iter().map(|s| s.parse::<Ipv4Net>().unwrap()).collect()
+}
+
+fn prefixes(set: &PrefixSet<Ipv4Net>) -> Vec<String> {
+    set.iter().map(|p| p.to_string()).collect()
+}
+
+#[test]
+fn set_normalize() {
+    let s = set(&[
+        "10.0.0.0/25",
+        "10.0.0.128/25",
+        "10.0.0.64/26",
+        "10.1.0.0/16",
+    ]);
+    assert_eq!(prefixes(&s), ["10.0.0.0/24", "10.1.0.0/16"]);
+    assert_eq!(s.len(), 2);
+
+    assert!(s.contains(&"10.0.0.0/24".parse().unwrap()));
+    assert!(!s.contains(&"10.0.0.0/23".parse().unwrap()));
+    assert!(s.contains_addr(&"10.1.2.3".parse().unwrap()));
+    assert!(!s.contains_addr(&"10.2.0.0".parse().unwrap()));
+}
+
+#[test]
+fn set_operations() {
+    let a = set(&["10.0.0.0/8"]);
+    let b = set(&["10.128.0.0/9", "11.0.0.0/8"]);
+
+    assert_eq!(prefixes(&a.union(&b)), ["10.0.0.0/7"]);
+    assert_eq!(prefixes(&a.intersection(&b)), ["10.128.0.0/9"]);
+    assert_eq!(prefixes(&a.difference(&b)), ["10.0.0.0/9"]);
+    assert_eq!(
+        prefixes(&a.symmetric_difference(&b)),
+        ["10.0.0.0/9", "11.0.0.0/8"]
+    );
+
+    assert!(set(&["10.1.0.0/16"]).is_subset(&a));
+    assert!(!b.is_subset(&a));
+    assert!(a.is_disjoint(&set(&["192.168.0.0/16"])));
+    assert!(!a.is_disjoint(&b));
+}
+
+#[test]
+fn set_complement() {
+    let s = set(&["128.0.0.0/1"]);
+    assert_eq!(prefixes(&s.complement()), ["0.0.0.0/1"]);
+
+    let s = set(&["10.0.0.0/8"]);
+    assert_eq!(
+        prefixes(&s.complement()),
+        [
+            "0.0.0.0/5",
+            "8.0.0.0/7",
+            "11.0.0.0/8",
+            "12.0.0.0/6",
+            "16.0.0.0/4",
+            "32.0.0.0/3",
+            "64.0.0.0/2",
+            "128.0.0.0/1"
+        ]
+    );
+    assert_eq!(
+        prefixes(&PrefixSet::<Ipv4Net>::new().complement()),
+        ["0.0.0.0/0"]
+    );
+    assert!(set(&["0.0.0.0/0"]).complement().is_empty());
+}
+
+#[test]
+fn set_insert_remove() {
+    let mut s = PrefixSet::new();
+    s.insert(&"10.0.0.0/25".parse::<Ipv4Net>().unwrap());
+    s.insert(&"10.0.0.128/25".parse().unwrap());
+    assert_eq!(prefixes(&s), ["10.0.0.0/24"]);
+
+    s.remove(&"10.0.0.64/26".parse().unwrap());
+    assert_eq!(prefixes(&s), ["10.0.0.0/26", "10.0.0.128/25"]);
+}
+
+#[test]
+fn set_from_ptree() {
+    let mut tree = Ptree::<Ipv4Net, i32>::new();
+    tree.add(&"10.0.0.0/24".parse().unwrap(), 1);
+    tree.add(&"10.0.1.0/24".parse().unwrap(), 2);
+    assert_eq!(prefixes(&PrefixSet::from(&tree)), ["10.0.0.0/23"]);
+}
@@ -0,0 +1,154 @@
+use ipnet::{Ipv4Net, Ipv6Net};
+use ptree::*;
+use std::net::Ipv4Addr;
+
+fn snapshot<P: Prefix + Copy>(ptree: &Ptree<P, u32>) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    ptree
+        .write_snapshot(&mut bytes, |d| d.to_be_bytes().to_vec())
+        .unwrap();
+    bytes
+}
+
+fn value(bytes: &[u8]) -> u32 {
+    u32::from_be_bytes(bytes.try_into().unwrap())
+}
+
+#[test]
+fn snapshot_lookup() {
+    let mut seed: u32 = 7;
+    let mut next = || {
+        seed = seed.wrapping_mul(1103515245).wrapping_add(12345);
+        seed
+    };
+
+    let mut top = Ptree::<Ipv4Net, u32>::new();
+    for i in 0..1000 {
+        let addr = Ipv4Addr::from(next() & 0x0fff_ff00 | 0x0a00_0000);
+        let len = 8 + (next() >> 8) % 17;
+        top.add(&Ipv4Net::new(addr, len as u8).unwrap(), i);
+    }
+    let bytes = snapshot(&top);
+    let snap = Snapshot::<Ipv4Net>::open(&bytes).unwrap();
+    assert_eq!(snap.len(), top.len());
+
+    let entries: Vec<(Ipv4Net, u32)> = snap.iter().map(|(p, d)| (p, value(d))).collect();
+    let expected: Vec<(Ipv4Net, u32)> = top
+        .iter()
+        .map(|n| (n.prefix, n.data.borrow().unwrap()))
+        .collect();
+    assert_eq!(entries, expected);
+
+    for _ in 0..1000 {
+        let addr = Ipv4Addr::from(next() & 0x0fff_ffff | 0x0a00_0000);
+        let prefix = Ipv4Net::new(addr, 32).unwrap();
+        let expected = top
+            .lookup(&prefix)
+            .node
+            .map(|n| (n.prefix, n.data.borrow().unwrap()));
+        assert_eq!(snap.lookup(&prefix).map(|(p, d)| (p, value(d))), expected);
+    }
+    for (prefix, data) in expected.iter() {
+        assert_eq!(snap.lookup_exact(prefix).map(value), Some(*data));
+    }
+    let missing: Ipv4Net = "192.168.0.0/16".parse().unwrap();
+    assert_eq!(snap.lookup(&missing), None);
+    assert_eq!(snap.lookup_exact(&missing), None);
+}
+
+#[test]
+fn snapshot_ipv6_file() {
+    let mut top = Ptree::<Ipv6Net, u32>::new_ipv6();
+    top.add_str("2001:db8::/32", 1).unwrap();
+    top.add_str("2001:db8:1::/48", 2).unwrap();
+    top.add_str("2001:db8:2::/48", 3).unwrap();
+
+    let path = std::env::temp_dir().join(format!("ptree-snapshot-{}", std::process::id()));
+    top.write_snapshot(std::fs::File::create(&path).unwrap(), |d| {
+        d.to_string().into_bytes()
+    })
+    .unwrap();
+    let bytes = std::fs::read(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    let snap = Snapshot::<Ipv6Net>::open(&bytes).unwrap();
+    let addr: Ipv6Net = "2001:db8:2::1/128".parse().unwrap();
+    assert_eq!(
+        snap.lookup(&addr),
+        Some(("2001:db8:2::/48".parse().unwrap(), &b"3"[..]))
+    );
+    let addr: Ipv6Net = "2001:db8:3::1/128".parse().unwrap();
+    assert_eq!(snap.lookup(&addr).map(|(_, d)| d), Some(&b"1"[..]));
+
+    assert!(matches!(
+        Snapshot::<Ipv4Net>::open(&bytes),
+        Err(Error::AddressFamilyMismatch(_))
+    ));
+}
+
+#[test]
+fn snapshot_empty() {
+    let bytes = snapshot(&Ptree::<Ipv4Net, u32>::new());
+    let snap = Snapshot::<Ipv4Net>::open(&bytes).unwrap();
+    assert!(snap.is_empty());
+    assert_eq!(snap.iter().count(), 0);
+    assert_eq!(snap.lookup(&"10.0.0.0/8".parse().unwrap()), None);
+}
+
+#[test]
+fn snapshot_rejects_corruption() {
+    let mut top = Ptree::<Ipv4Net, u32>::new();
+    top.add_str("10.0.0.0/8", 1).unwrap();
+    top.add_str("10.1.0.0/16", 2).unwrap();
+    let bytes = snapshot(&top);
+
+    for index in 0..bytes.len() {
+        let mut corrupt = bytes.clone();
+        corrupt[index] ^= 0x10;
+        assert!(
+            Snapshot::<Ipv4Net>::open(&corrupt).is_err(),
+            "byte {}",
+            index
+        );
+    }
+    assert!(matches!(
+        Snapshot::<Ipv4Net>::open(&bytes[..bytes.len() - 1]),
+        Err(Error::Truncated)
+    ));
+    assert!(matches!(
+        Snapshot::<Ipv4Net>::open(&bytes[..10]),
+        Err(Error::Truncated)
+    ));
+
+    let mut corrupt = bytes.clone();
+    corrupt[30] ^= 0x01;
+    assert!(matches!(
+        Snapshot::<Ipv4Net>::open(&corrupt),
+        Err(Error::Checksum)
+    ));
+    let mut corrupt = bytes.clone();
+    corrupt[5] = 2;
+    assert!(matches!(
+        Snapshot::<Ipv4Net>::open(&corrupt),
+        Err(Error::UnsupportedVersion(2))
+    ));
+    let mut corrupt = bytes;
+    corrupt[0] = b'X';
+    assert!(matches!(
+        Snapshot::<Ipv4Net>::open(&corrupt),
+        Err(Error::InvalidData(_))
+    ));
+}
+
+#[test]
+fn snapshot_data_set_on_nodes() {
+    let mut top = Ptree::<Ipv4Net, u32>::new();
+    top.add_str("10.0.0.0/8", 1).unwrap();
+    let prefix: Ipv4Net = "10.1.0.0/16".parse().unwrap();
+    top.insert(&prefix).node.unwrap().set_data(2);
+
+    let bytes = snapshot(&top);
+    let snap = Snapshot::<Ipv4Net>::open(&bytes).unwrap();
+    assert_eq!(snap.len(), 2);
+    assert_eq!(snap.lookup_exact(&prefix).map(value), Some(2));
+}
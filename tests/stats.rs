@@ -0,0 +1,55 @@
+use ipnet::{Ipv4Net, Ipv6Net};
+use ptree::*;
+
+fn route_ipv4_add(ptree: &mut Ptree<Ipv4Net, i32>, str: &str, data: i32) {
+    let prefix: Ipv4Net = str.parse().unwrap();
+    ptree.add(&prefix, data);
+}
+
+#[test]
+fn stats_coverage() {
+    let mut top = Ptree::<Ipv4Net, i32>::new();
+    route_ipv4_add(&mut top, "10.0.0.0/8", 1);
+    route_ipv4_add(&mut top, "10.1.0.0/16", 2);
+    route_ipv4_add(&mut top, "10.1.1.0/24", 1);
+    route_ipv4_add(&mut top, "10.2.0.0/16", 1);
+    route_ipv4_add(&mut top, "192.168.0.0/24", 3);
+
+    let stats = top.stats();
+    assert_eq!(stats.entries, 5);
+    assert_eq!(
+        stats.lengths.into_iter().collect::<Vec<_>>(),
+        [(8, 1), (16, 2), (24, 2)]
+    );
+    assert_eq!(stats.covered, (1 << 24) + 256);
+    assert_eq!(stats.covered_by_data[&1], (1 << 24) - 65536 + 256);
+    assert_eq!(stats.covered_by_data[&2], 65536 - 256);
+    assert_eq!(stats.covered_by_data[&3], 256);
+}
+
+#[test]
+fn stats_default_route() {
+    let mut top = Ptree::<Ipv4Net, i32>::new();
+    assert_eq!(top.stats().covered, 0);
+
+    route_ipv4_add(&mut top, "0.0.0.0/0", 0);
+    route_ipv4_add(&mut top, "10.0.0.1/32", 1);
+    let stats = top.stats();
+    assert_eq!(stats.covered, 1 << 32);
+    assert_eq!(stats.covered_by_data[&0], (1 << 32) - 1);
+    assert_eq!(stats.covered_by_data[&1], 1);
+}
+
+#[test]
+fn stats_ipv6_default_route() {
+    // 2^128 does not fit in a u128, so the count is one short.
+    let mut top = Ptree::<Ipv6Net, i32>::new_ipv6();
+    top.add_str("::/0", 0).unwrap();
+    assert_eq!(top.stats().covered, u128::MAX);
+
+    top.add_str("2001:db8::1/128", 1).unwrap();
+    let stats = top.stats();
+    assert_eq!(stats.covered, u128::MAX);
+    assert_eq!(stats.covered_by_data[&0], u128::MAX - 1);
+    assert_eq!(stats.covered_by_data[&1], 1);
+}
@@ -0,0 +1,94 @@
+use ipnet::Ipv4Net;
+use ptree::*;
+use std::sync::mpsc::TryRecvError;
+
+fn p(str: &str) -> Ipv4Net {
+    str.parse().unwrap()
+}
+
+fn received(subscription: &Subscription<Ipv4Net, u32>) -> Vec<Change<Ipv4Net, u32>> {
+    subscription.receiver().try_iter().collect()
+}
+
+#[test]
+fn subscribe_subtree() {
+    let mut top = Ptree::<Ipv4Net, u32>::new();
+    let customer = top.subscribe(&p("10.1.0.0/16"), 16);
+    let all = top.subscribe(&p("0.0.0.0/0"), 16);
+
+    top.add(&p("10.0.0.0/8"), 1);
+    top.add(&p("10.1.2.0/24"), 2);
+    top.add(&p("10.1.0.0/16"), 3);
+    top.add(&p("10.2.0.0/16"), 4);
+    top.add(&p("10.1.2.0/24"), 5);
+    top.delete(&p("10.1.2.0/24"));
+    assert_eq!(
+        received(&customer),
+        [
+            Change::Added(p("10.1.2.0/24"), 2),
+            Change::Added(p("10.1.0.0/16"), 3),
+            Change::Replaced(p("10.1.2.0/24"), 2, 5),
+            Change::Removed(p("10.1.2.0/24"), 5),
+        ]
+    );
+    assert_eq!(received(&all).len(), 6);
+    assert_eq!(customer.dropped(), 0);
+
+    assert!(top.unsubscribe(&customer));
+    assert!(!top.unsubscribe(&customer));
+    top.add(&p("10.1.3.0/24"), 6);
+    assert_eq!(
+        customer.receiver().try_recv(),
+        Err(TryRecvError::Disconnected)
+    );
+    assert_eq!(received(&all), [Change::Added(p("10.1.3.0/24"), 6)]);
+}
+
+#[test]
+fn subscribe_bounded() {
+    let mut top = Ptree::<Ipv4Net, u32>::new();
+    let subscription = top.subscribe(&p("10.0.0.0/8"), 2);
+    for i in 0..5 {
+        top.add(&p(&format!("10.{}.0.0/16", i)), i);
+    }
+    assert_eq!(subscription.dropped(), 3);
+    assert_eq!(
+        received(&subscription),
+        [
+            Change::Added(p("10.0.0.0/16"), 0),
+            Change::Added(p("10.1.0.0/16"), 1)
+        ]
+    );
+    top.delete(&p("10.4.0.0/16"));
+    assert_eq!(
+        received(&subscription),
+        [Change::Removed(p("10.4.0.0/16"), 4)]
+    );
+    assert_eq!(subscription.dropped(), 3);
+
+    // A dropped subscription is removed at the next change, without
+    // affecting the table.
+    let id = subscription.id();
+    drop(subscription);
+    top.add(&p("192.168.0.0/16"), 5);
+    assert_eq!(top.len(), 5);
+    assert!(top.remove_observer(id).is_none());
+}
+
+#[test]
+fn subscribe_other_thread() {
+    let mut top = Ptree::<Ipv4Net, u32>::new();
+    let subscription = top.subscribe(&p("192.168.0.0/16"), 8);
+    let handle =
+        std::thread::spawn(move || subscription.receiver().iter().take(2).collect::<Vec<_>>());
+    top.add(&p("10.0.0.0/8"), 1);
+    top.add(&p("192.168.1.0/24"), 2);
+    top.delete(&p("192.168.1.0/24"));
+    assert_eq!(
+        handle.join().unwrap(),
+        [
+            Change::Added(p("192.168.1.0/24"), 2),
+            Change::Removed(p("192.168.1.0/24"), 2)
+        ]
+    );
+}
@@ -0,0 +1,110 @@
+use ipnet::Ipv4Net;
+use ptree::*;
+
+fn route_ipv4_add(ptree: &mut Ptree<Ipv4Net, i32>, str: &str, data: i32) {
+    let prefix: Ipv4Net = str.parse().unwrap();
+    ptree.add(&prefix, data);
+}
+
+fn route_ipv4_delete(ptree: &mut Ptree<Ipv4Net, i32>, str: &str) {
+    let prefix: Ipv4Net = str.parse().unwrap();
+    ptree.delete(&prefix);
+}
+
+fn prefix(str: &str) -> Ipv4Net {
+    str.parse().unwrap()
+}
+
+#[test]
+fn validate_add_delete() {
+    let mut top = Ptree::<Ipv4Net, i32>::new();
+    assert!(top.validate().is_ok());
+
+    route_ipv4_add(&mut top, "10.0.0.0/8", 8);
+    route_ipv4_add(&mut top, "10.1.0.0/16", 16);
+    route_ipv4_add(&mut top, "10.2.0.0/16", 16);
+    route_ipv4_add(&mut top, "10.2.1.0/24", 24);
+    route_ipv4_add(&mut top, "192.168.0.0/24", 24);
+    route_ipv4_add(&mut top, "10.1.0.0/16", 17);
+    assert_eq!(top.len(), 5);
+    assert!(top.validate().is_ok());
+
+    route_ipv4_delete(&mut top, "10.0.0.0/8");
+    route_ipv4_delete(&mut top, "10.2.0.0/16");
+    route_ipv4_delete(&mut top, "172.16.0.0/12");
+    assert_eq!(top.len(), 3);
+    assert!(top.validate().is_ok());
+
+    route_ipv4_delete(&mut top, "10.1.0.0/16");
+    route_ipv4_delete(&mut top, "10.2.1.0/24");
+    route_ipv4_delete(&mut top, "192.168.0.0/24");
+    assert!(top.is_empty());
+    assert!(top.validate().is_ok());
+}
+
+#[test]
+fn validate_after_delete_leaves_one_child() {
+    let mut top = Ptree::<Ipv4Net, i32>::new();
+    route_ipv4_add(&mut top, "10.1.0.0/16", 1);
+    route_ipv4_add(&mut top, "10.2.0.0/16", 2);
+    route_ipv4_add(&mut top, "10.3.0.0/16", 3);
+    assert!(top.validate().is_ok());
+
+    // The glue node 10.0.0.0/14 loses one of its two children.
+    route_ipv4_delete(&mut top, "10.1.0.0/16");
+    assert!(top.validate().is_ok());
+    assert_eq!(top.len(), 2);
+
+    route_ipv4_delete(&mut top, "10.3.0.0/16");
+    assert!(top.validate().is_ok());
+    route_ipv4_delete(&mut top, "10.2.0.0/16");
+    assert!(top.validate().is_ok());
+    assert!(top.is_empty());
+}
+
+#[test]
+fn len_with_data_set_on_nodes() {
+    let mut top = Ptree::<Ipv4Net, i32>::new();
+    top.insert(&prefix("10.0.0.0/8")).node.unwrap().set_data(1);
+    route_ipv4_add(&mut top, "10.1.0.0/16", 16);
+    assert_eq!(top.len(), 2);
+
+    route_ipv4_delete(&mut top, "10.0.0.0/8");
+    route_ipv4_delete(&mut top, "10.0.0.0/8");
+    assert_eq!(top.len(), 1);
+    top.lookup_exact(&prefix("10.1.0.0/16"))
+        .node
+        .unwrap()
+        .unset_data();
+    assert!(top.is_empty());
+}
+
+#[test]
+fn validate_detects_violations() {
+    let mut top = Ptree::<Ipv4Net, i32>::new();
+    route_ipv4_add(&mut top, "10.0.0.0/8", 8);
+    route_ipv4_add(&mut top, "10.1.0.0/16", 16);
+    route_ipv4_add(&mut top, "10.2.0.0/16", 16);
+
+    // A leaf losing its data behind the tree's back becomes a glue node
+    // with no children.
+    let leaf = top.lookup_exact(&prefix("10.1.0.0/16")).node.unwrap();
+    leaf.unset_data();
+    leaf.parent.replace(None);
+
+    let err = top.validate().unwrap_err();
+    assert_eq!(
+        err.violations,
+        [
+            Violation::WrongParent {
+                parent: prefix("10.0.0.0/14"),
+                child: prefix("10.1.0.0/16")
+            },
+            Violation::Glue {
+                prefix: prefix("10.1.0.0/16"),
+                children: 0
+            },
+        ]
+    );
+    assert!(err.to_string().starts_with("2 trie invariant violations"));
+}